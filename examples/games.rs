@@ -2,8 +2,8 @@ use futures::{StreamExt, TryStreamExt};
 use speedrun_api::{
     api::{
         games::{
-            Game, GameCategories, GameDerivedGames, GameLevels, GameRecords, GameVariables, Games,
-            LeaderboardScope,
+            BulkGames, Game, GameCategories, GameDerivedGames, GameLevels, GameRecords,
+            GameVariables, Games, LeaderboardScope,
         },
         AsyncQuery, PagedEndpointExt,
     },
@@ -62,5 +62,12 @@ pub async fn main() -> SpeedrunApiResult<()> {
     let records: Vec<types::Leaderboard> = endpoint.stream(&client).try_collect().await?;
     println!("{:#?}", records);
 
+    let endpoint = BulkGames::builder().released(2003).build().unwrap();
+    let mut catalog = Vec::new();
+    let count = endpoint
+        .write_json_lines_async(&client, 4, &mut catalog)
+        .await?;
+    println!("exported {} games ({} bytes)", count, catalog.len());
+
     Ok(())
 }
@@ -1,6 +1,6 @@
 use speedrun_api::{
     api::{
-        leaderboards::{FullGameLeaderboard, IndividualLevelLeaderboard},
+        leaderboards::{FullGameLeaderboard, IndividualLevelLeaderboard, LeaderboardEmbeds},
         AsyncQuery,
     },
     error::SpeedrunApiResult,
@@ -32,6 +32,17 @@ pub async fn main() -> SpeedrunApiResult<()> {
     let leaderboard: types::Leaderboard = endpoint.query_async(&client).await?;
     println!("{:#?}", leaderboard);
 
+    let endpoint = FullGameLeaderboard::builder()
+        .game("xldev513")
+        .category("rklg3rdn")
+        .embed(LeaderboardEmbeds::Category)
+        .embed(LeaderboardEmbeds::Players)
+        .build()
+        .unwrap();
+    let leaderboard: types::LeaderboardWithEmbeds = endpoint.query_async(&client).await?;
+    println!("{:#?}", leaderboard.category());
+    println!("{:#?}", leaderboard.players());
+
     // This game/category combonation does not exist, however this is the
     // example used by the API documentation.
     let endpoint = FullGameLeaderboard::builder()
@@ -3,7 +3,8 @@ use std::future;
 use futures::{StreamExt, TryStreamExt};
 use speedrun_api::{
     api::{
-        runs::{Run, RunStatus, Runs, RunsSorting},
+        games::GameEmbeds,
+        runs::{Run, RunEmbeds, RunStatus, Runs, RunsSorting},
         AsyncQuery, Direction, PagedEndpointExt,
     },
     error::SpeedrunApiResult,
@@ -82,5 +83,15 @@ pub async fn main() -> SpeedrunApiResult<()> {
     let run: types::Run = endpoint.query_async(&client).await?;
     println!("{:#?}", run);
 
+    let endpoint = Run::builder()
+        .id("90y6pm7e")
+        .embed(RunEmbeds::GameWith(GameEmbeds::Platforms))
+        .embed(RunEmbeds::Players)
+        .build()
+        .unwrap();
+    let run: types::RunWithEmbeds = endpoint.query_async(&client).await?;
+    println!("{:#?}", run.game());
+    println!("{:#?}", run.players());
+
     Ok(())
 }
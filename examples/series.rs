@@ -1,7 +1,6 @@
 use std::future;
 
 use futures::{StreamExt, TryStreamExt};
-use serde::Deserialize;
 use speedrun_api::{
     api::{
         series::{ListSeries, Series, SeriesGames, SeriesSorting},
@@ -75,18 +74,14 @@ pub async fn main() -> SpeedrunApiResult<()> {
             })
             .await?;
     }
-    #[derive(Debug, Deserialize)]
-    struct BulkGame {
-        id: String,
-        names: types::Names,
-        abbreviation: String,
-        weblink: String,
-    }
     let endpoint = builder.bulk(true).build().unwrap();
     endpoint
         .stream(&client)
-        .try_for_each_concurrent(10, |game: BulkGame| {
-            println!("{}", game.names.international);
+        .try_for_each_concurrent(10, |game: types::BulkGame| {
+            println!(
+                "{} [{}] ({}) {}",
+                game.names.international, game.abbreviation, game.id, game.weblink
+            );
             future::ready(Ok(()))
         })
         .await?;
@@ -4,7 +4,8 @@
 //!
 //! # Example
 //!
-//! ```rust ,no_run
+#![cfg_attr(feature = "async", doc = "```rust ,no_run")]
+#![cfg_attr(not(feature = "async"), doc = "```rust ,ignore")]
 //! use futures::{StreamExt, TryStreamExt};
 //! use serde::Deserialize;
 //! use speedrun_api::{
@@ -47,19 +48,22 @@
 //!     let single_page_endpoint = paginated_endpoint
 //!         .single_page()
 //!         .offset(100)
-//!         .max(100)
-//!         .build()
-//!         .unwrap();
+//!         .page_size(100)
+//!         .build();
 //!     // This wrapped endpoint can be queried like any normal endpoint, but always returns a
-//!     // `Vec<T: Deserialize>`.
-//!     let games: Vec<Game> = single_page_endpoint.query_async(&client).await?;
+//!     // `Vec<T: Deserialize>` along with the pagination information.
+//!     let (games, _pagination): (Vec<Game>, _) = single_page_endpoint.query_async(&client).await?;
+//!
+//!     Ok(())
 //! }
 //! ```
 
 mod client;
 mod common;
-mod endpoint;
+pub(crate) mod endpoint;
 mod error;
+mod executor;
+mod ignore;
 mod pagination;
 mod query;
 mod utils;
@@ -73,6 +77,7 @@ pub mod genres;
 pub mod guests;
 pub mod leaderboards;
 pub mod levels;
+pub mod links;
 pub mod notifications;
 pub mod platforms;
 pub mod profile;
@@ -85,7 +90,14 @@ pub mod variables;
 
 pub use client::{AsyncClient, Client, RestClient};
 pub use common::{CategoriesSorting, Direction, VariablesSorting};
-pub use error::ApiError;
-pub use pagination::{Pageable, PagedEndpointExt, PagedIter, SinglePage, SinglePageBuilder};
-pub use query::AsyncQuery;
+pub use error::{ApiError, FieldError, SpeedrunApiErrorBody};
+pub use ignore::{ignore, Ignore};
+pub use pagination::{
+    Page, PageOptions, PageProgress, Pageable, PagedEndpointExt, PagedIter, SinglePage,
+    SinglePageBuilder, MAX_PAGE_SIZE,
+};
+pub use query::{AsyncQuery, AsyncRawQuery, Query, RawQuery, RawResponse};
 pub use crate::types::Root;
+
+pub(crate) use endpoint::Authenticated;
+pub(crate) use utils::fnv1a;
@@ -8,19 +8,22 @@ use http::Method;
 use serde::{Deserialize, Serialize};
 
 use super::{
-    endpoint::Endpoint, error::BodyError, leaderboards::LeaderboardEmbeds, Direction, Pageable,
-    VariablesSorting,
+    endpoint::Endpoint, error::BodyError, games::GameEmbeds, leaderboards::LeaderboardEmbeds,
+    Direction, Pageable, VariablesSorting,
 };
 
 /// Embeds available for categories.
 ///
-/// NOTE: Embeds can be nested. That is not handled by this API.
+/// Embeds can be nested, e.g. [`CategoryEmbeds::GameWith`] with
+/// [`GameEmbeds::Platforms`] embeds `game.platforms`.
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub enum CategoryEmbeds {
     /// Embed the `game` resource this category belongs to.
     Game,
     /// Embed `variables` applicable to this category
     Variables,
+    /// Embed the `game` resource, with a nested embed (`game.<embed>`).
+    GameWith(Box<GameEmbeds>),
 }
 
 /// Represents a category ID
@@ -158,10 +161,11 @@ impl<'a> CategoryRecordsBuilder<'a> {
 }
 
 impl CategoryEmbeds {
-    fn as_str(&self) -> &'static str {
+    pub(crate) fn path(&self) -> Cow<'static, str> {
         match self {
-            CategoryEmbeds::Game => "game",
-            CategoryEmbeds::Variables => "variables",
+            CategoryEmbeds::Game => "game".into(),
+            CategoryEmbeds::Variables => "variables".into(),
+            CategoryEmbeds::GameWith(embed) => format!("game.{}", embed.path()).into(),
         }
     }
 }
@@ -208,9 +212,9 @@ impl Endpoint for CategoryRecords<'_> {
     }
 }
 
-impl From<&CategoryEmbeds> for &'static str {
+impl From<&CategoryEmbeds> for Cow<'static, str> {
     fn from(value: &CategoryEmbeds) -> Self {
-        value.as_str()
+        value.path()
     }
 }
 
@@ -2,10 +2,11 @@ use std::error::Error;
 
 use async_trait::async_trait;
 use bytes::Bytes;
-use http::{request::Builder as RequestBuilder, Response};
+use http::{request::Builder as RequestBuilder, Request, Response};
 use url::Url;
 
 use super::error::ApiError;
+use crate::middleware::RequestInfo;
 
 /// A trait representing a client which can communicate with speedrun.com via
 /// REST
@@ -20,6 +21,39 @@ pub trait RestClient {
 
     /// If the client has an API key
     fn has_api_key(&self) -> bool;
+
+    /// Called with every request made by a query, before it is sent.
+    ///
+    /// This is the extension point for inspecting and modifying requests, and
+    /// is where the clients of this crate run their [`Middleware`]. Clients
+    /// that wrap another client should forward this to the inner client. The
+    /// default implementation leaves the request unchanged.
+    ///
+    /// [`Middleware`]: crate::Middleware
+    fn intercept_request(
+        &self,
+        request: &mut Request<Vec<u8>>,
+    ) -> Result<(), ApiError<Self::Error>> {
+        let _ = request;
+        Ok(())
+    }
+
+    /// Called with every response received by a query, before its status is
+    /// checked and its body is decoded. `request` describes the request the
+    /// response is for.
+    ///
+    /// This is not called when the client fails to send the request, e.g.
+    /// because of a connection error. Clients that wrap another client should
+    /// forward this to the inner client. The default implementation leaves
+    /// the response unchanged.
+    fn intercept_response(
+        &self,
+        request: &RequestInfo,
+        response: &mut Response<Bytes>,
+    ) -> Result<(), ApiError<Self::Error>> {
+        let _ = (request, response);
+        Ok(())
+    }
 }
 
 /// A trait representing a client which can communicate with speedrun.com
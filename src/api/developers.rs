@@ -10,10 +10,11 @@ use serde::{Deserialize, Serialize};
 use super::{endpoint::Endpoint, Direction, Pageable};
 
 /// Sorting options for developers
-#[derive(Debug, Serialize, Clone, Copy)]
+#[derive(Debug, Serialize, Clone, Copy, Default)]
 #[serde(rename_all = "kebab-case")]
 pub enum DevelopersSorting {
     /// Sort alphanumerically by developer name (default)
+    #[default]
     Name,
 }
 
@@ -79,12 +80,6 @@ impl<'a> Developer<'a> {
     }
 }
 
-impl Default for DevelopersSorting {
-    fn default() -> Self {
-        Self::Name
-    }
-}
-
 impl Endpoint for Developers {
     fn method(&self) -> http::Method {
         Method::GET
@@ -1,17 +1,22 @@
 use std::borrow::Cow;
 
 use async_trait::async_trait;
-use http::{header, Method, Request};
+use http::Method;
 use log::debug;
 use serde::de::DeserializeOwned;
-use crate::types::Root;
 
 use super::{
     ApiError,
     AsyncClient,
-    Client, error::BodyError, query::{self, AsyncQuery, Query},
+    Client, error::BodyError, executor::PreparedRequest,
+    query::{AsyncQuery, AsyncRawQuery, Query, RawQuery, RawResponse},
 };
 
+/// Marker added to the extensions of requests for endpoints that require
+/// authentication.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Authenticated;
+
 pub trait Endpoint {
     fn method(&self) -> Method;
     fn endpoint(&self) -> Cow<'static, str>;
@@ -48,32 +53,8 @@ where
     T: DeserializeOwned,
     C: Client,
 {
-    fn query(&self, client: &C) -> Result<T, super::ApiError<C::Error>> {
-        if self.requires_authentication() && !client.has_api_key() {
-            return Err(ApiError::RequiresAuthentication);
-        }
-        let mut url = client.rest_endpoint(&self.endpoint())?;
-        self.set_query_parameters(&mut url)?;
-
-        let req = Request::builder()
-            .method(self.method())
-            .uri(query::url_to_http_uri(url));
-        let (req, data) = if let Some((mime, data)) = self.body()? {
-            let req = req.header(header::CONTENT_TYPE, mime);
-            (req, data)
-        } else {
-            (req, Vec::new())
-        };
-        let rsp = client.rest(req, data)?;
-        let status = rsp.status();
-        let value = serde_json::from_slice(rsp.body())?;
-        if !status.is_success() {
-            return Err(ApiError::from_speedrun_api(value));
-        }
-
-        serde_json::from_value::<Root<T>>(value)
-            .map(|root| root.data)
-            .map_err(ApiError::data_type::<T>)
+    fn query(&self, client: &C) -> Result<T, ApiError<C::Error>> {
+        PreparedRequest::new(self, client)?.send(client)?.data()
     }
 }
 
@@ -85,31 +66,33 @@ where
     C: AsyncClient + Sync,
 {
     async fn query_async(&self, client: &C) -> Result<T, ApiError<C::Error>> {
-        if self.requires_authentication() && !client.has_api_key() {
-            return Err(ApiError::RequiresAuthentication);
-        }
-        let mut url = client.rest_endpoint(&self.endpoint())?;
-        self.set_query_parameters(&mut url)?;
-
-        let req = Request::builder()
-            .method(self.method())
-            .uri(query::url_to_http_uri(url));
-        let (req, data) = if let Some((mime, data)) = self.body()? {
-            let req = req.header(header::CONTENT_TYPE, mime);
-            (req, data)
-        } else {
-            (req, Vec::new())
-        };
+        PreparedRequest::new(self, client)?
+            .send_async(client)
+            .await?
+            .data()
+    }
+}
 
-        let rsp = client.rest_async(req, data).await?;
-        let status = rsp.status();
-        let value = serde_json::from_slice(rsp.body())?;
-        if !status.is_success() {
-            return Err(ApiError::from_speedrun_api(value));
-        }
+impl<E, C> RawQuery<C> for E
+where
+    E: Endpoint,
+    C: Client,
+{
+    fn query_raw(&self, client: &C) -> Result<RawResponse, ApiError<C::Error>> {
+        PreparedRequest::new(self, client)?.send(client)?.raw()
+    }
+}
 
-        serde_json::from_value::<Root<T>>(value)
-            .map(|value| value.data)
-            .map_err(ApiError::data_type::<T>)
+#[async_trait]
+impl<E, C> AsyncRawQuery<C> for E
+where
+    E: Endpoint + Sync,
+    C: AsyncClient + Sync,
+{
+    async fn query_raw_async(&self, client: &C) -> Result<RawResponse, ApiError<C::Error>> {
+        PreparedRequest::new(self, client)?
+            .send_async(client)
+            .await?
+            .raw()
     }
 }
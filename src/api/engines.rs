@@ -10,10 +10,11 @@ use serde::{Deserialize, Serialize};
 use super::{endpoint::Endpoint, Direction, Pageable};
 
 /// Sorting options for engines
-#[derive(Debug, Serialize, Clone, Copy)]
+#[derive(Debug, Serialize, Clone, Copy, Default)]
 #[serde(rename_all = "kebab-case")]
 pub enum EnginesSorting {
     /// Sort alphanumerically by engine name (default)
+    #[default]
     Name,
 }
 
@@ -79,12 +80,6 @@ impl<'a> Engine<'a> {
     }
 }
 
-impl Default for EnginesSorting {
-    fn default() -> Self {
-        Self::Name
-    }
-}
-
 impl Endpoint for Engines {
     fn method(&self) -> http::Method {
         Method::GET
@@ -1,7 +1,16 @@
-use std::{any, error::Error};
+use std::{
+    any,
+    error::Error,
+    fmt::{self, Display},
+    time::Duration,
+};
 
+use http::{HeaderMap, StatusCode};
+use serde::{Deserialize, Deserializer};
 use thiserror::Error;
 
+use crate::types::Link;
+
 /// Errors that occur when creating form data.
 #[derive(Debug, Error)]
 #[non_exhaustive]
@@ -32,9 +41,44 @@ where
     /// The URL failed to parse.
     #[error("url parse error: {0}")]
     Parse(#[from] url::ParseError),
+    /// The request could not be built.
+    #[error("failed to build request: {0}")]
+    Request(#[from] http::Error),
     /// Speedrun.com returned an error
     #[error("Speedrun.com server error: {0}")]
-    SpeedrunApi(String),
+    SpeedrunApi(SpeedrunApiErrorBody),
+    /// The requested resource does not exist.
+    #[error("not found: {0}")]
+    NotFound(SpeedrunApiErrorBody),
+    /// The API key is missing or invalid.
+    #[error("unauthorized: {0}")]
+    Unauthorized(SpeedrunApiErrorBody),
+    /// The authenticated user is not allowed to perform the request.
+    #[error("forbidden: {0}")]
+    Forbidden(SpeedrunApiErrorBody),
+    /// The submitted data was rejected. See
+    /// [`SpeedrunApiErrorBody::errors`] for the problems found.
+    #[error("validation failed: {0}")]
+    ValidationFailed(SpeedrunApiErrorBody),
+    /// Too many requests were made.
+    #[error("rate limited: {body}")]
+    RateLimited {
+        /// How long to wait before retrying, from the `Retry-After` header.
+        retry_after: Option<Duration>,
+        /// The error returned by speedrun.com.
+        body: SpeedrunApiErrorBody,
+    },
+    /// The response body was not JSON, e.g. an HTML error page returned by a
+    /// proxy in front of speedrun.com.
+    #[error("unexpected response ({status}, content type {content_type:?}): {body}")]
+    NonJson {
+        /// The HTTP status of the response.
+        status: StatusCode,
+        /// The `Content-Type` header of the response, if any.
+        content_type: Option<String>,
+        /// The start of the response body.
+        body: String,
+    },
     /// Speedrun.com returned an unknown error
     #[error("Unknown speedrun.com server error: {0:?}")]
     Unknown(serde_json::Value),
@@ -49,6 +93,14 @@ where
     /// The endpoint requires an API key to use, but none was provided.
     #[error("Endpoint requires authentication, but no API key was provided")]
     RequiresAuthentication,
+    /// The requested page size is outside of the range allowed by the endpoint.
+    #[error("invalid page size {requested}, must be between 1 and {max}")]
+    InvalidPageSize {
+        /// The page size that was requested.
+        requested: usize,
+        /// The largest page size allowed by the endpoint.
+        max: usize,
+    },
 }
 
 impl<E> ApiError<E>
@@ -60,16 +112,70 @@ where
         Self::Client(source)
     }
 
-    pub(crate) fn from_speedrun_api(val: serde_json::Value) -> Self {
-        // let val = val.pointer("/message");
-        if let Some(val) = val.pointer("/message") {
-            if let Some(msg) = val.as_str() {
-                Self::SpeedrunApi(msg.into())
-            } else {
-                Self::Unknown(val.clone())
+    /// Convert the client error contained in this error.
+    pub(crate) fn map_client<F, E2>(self, f: F) -> ApiError<E2>
+    where
+        F: FnOnce(E) -> E2,
+        E2: Error + Send + Sync + 'static,
+    {
+        match self {
+            Self::Body(source) => ApiError::Body(source),
+            Self::Client(source) => ApiError::Client(f(source)),
+            Self::Json(source) => ApiError::Json(source),
+            Self::Parse(source) => ApiError::Parse(source),
+            Self::Request(source) => ApiError::Request(source),
+            Self::SpeedrunApi(body) => ApiError::SpeedrunApi(body),
+            Self::NotFound(body) => ApiError::NotFound(body),
+            Self::Unauthorized(body) => ApiError::Unauthorized(body),
+            Self::Forbidden(body) => ApiError::Forbidden(body),
+            Self::ValidationFailed(body) => ApiError::ValidationFailed(body),
+            Self::RateLimited { retry_after, body } => ApiError::RateLimited { retry_after, body },
+            Self::NonJson {
+                status,
+                content_type,
+                body,
+            } => ApiError::NonJson {
+                status,
+                content_type,
+                body,
+            },
+            Self::Unknown(value) => ApiError::Unknown(value),
+            Self::DataType { source, typename } => ApiError::DataType { source, typename },
+            Self::RequiresAuthentication => ApiError::RequiresAuthentication,
+            Self::InvalidPageSize { requested, max } => {
+                ApiError::InvalidPageSize { requested, max }
             }
-        } else {
-            Self::Unknown(val.clone())
+        }
+    }
+
+    /// Create an error from an unsuccessful response.
+    pub(crate) fn from_response(
+        status: StatusCode,
+        headers: &HeaderMap,
+        value: serde_json::Value,
+    ) -> Self {
+        if !value.is_object() {
+            return Self::Unknown(value);
+        }
+        let mut body = match SpeedrunApiErrorBody::deserialize(&value) {
+            Ok(body) => body,
+            Err(_) => return Self::Unknown(value),
+        };
+        body.status = status;
+        if crate::rate_limit::is_rate_limited(status) {
+            return Self::RateLimited {
+                retry_after: crate::rate_limit::retry_after(headers),
+                body,
+            };
+        }
+        match status {
+            StatusCode::NOT_FOUND => Self::NotFound(body),
+            StatusCode::UNAUTHORIZED => Self::Unauthorized(body),
+            StatusCode::FORBIDDEN => Self::Forbidden(body),
+            StatusCode::BAD_REQUEST | StatusCode::UNPROCESSABLE_ENTITY => {
+                Self::ValidationFailed(body)
+            }
+            _ => Self::SpeedrunApi(body),
         }
     }
 
@@ -80,3 +186,192 @@ where
         }
     }
 }
+
+/// The body of an error response from speedrun.com.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[non_exhaustive]
+pub struct SpeedrunApiErrorBody {
+    /// The HTTP status of the response.
+    #[serde(skip)]
+    pub status: StatusCode,
+    /// A description of the error.
+    #[serde(default)]
+    pub message: String,
+    /// Problems found with the submitted data, for validation errors.
+    #[serde(default)]
+    pub errors: Vec<FieldError>,
+    /// Links to related resources, e.g. documentation.
+    #[serde(default)]
+    pub links: Vec<Link>,
+}
+
+impl Display for SpeedrunApiErrorBody {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({})", self.message, self.status)?;
+        for error in &self.errors {
+            write!(f, "; {}", error)?;
+        }
+        Ok(())
+    }
+}
+
+/// A problem with a single field of submitted data.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldError {
+    /// The field the problem was found in, if given.
+    pub field: Option<String>,
+    /// A description of the problem.
+    pub message: String,
+}
+
+impl Display for FieldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.field {
+            Some(field) => write!(f, "{}: {}", field, self.message),
+            None => f.write_str(&self.message),
+        }
+    }
+}
+
+/// Field errors are returned either as plain messages, or as objects naming
+/// the field.
+impl<'de> Deserialize<'de> for FieldError {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Repr {
+            Message(String),
+            Field {
+                #[serde(alias = "name", alias = "path", default)]
+                field: Option<String>,
+                #[serde(alias = "error")]
+                message: String,
+            },
+        }
+
+        Ok(match Repr::deserialize(deserializer)? {
+            Repr::Message(message) => FieldError {
+                field: None,
+                message,
+            },
+            Repr::Field { field, message } => FieldError { field, message },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use http::{header, HeaderMap, HeaderValue, StatusCode};
+    use serde_json::json;
+
+    use super::{ApiError, FieldError};
+    use crate::mock::MockError;
+
+    fn from_response(status: u16, value: serde_json::Value) -> ApiError<MockError> {
+        ApiError::from_response(
+            StatusCode::from_u16(status).unwrap(),
+            &HeaderMap::new(),
+            value,
+        )
+    }
+
+    #[test]
+    fn maps_status_codes() {
+        let body = json!({ "status": 0, "message": "error" });
+        assert!(matches!(
+            from_response(404, body.clone()),
+            ApiError::NotFound(_)
+        ));
+        assert!(matches!(
+            from_response(401, body.clone()),
+            ApiError::Unauthorized(_)
+        ));
+        assert!(matches!(
+            from_response(403, body.clone()),
+            ApiError::Forbidden(_)
+        ));
+        assert!(matches!(
+            from_response(400, body.clone()),
+            ApiError::ValidationFailed(_)
+        ));
+        assert!(matches!(
+            from_response(422, body.clone()),
+            ApiError::ValidationFailed(_)
+        ));
+        assert!(matches!(from_response(500, body), ApiError::SpeedrunApi(_)));
+        assert!(matches!(
+            from_response(500, json!("oops")),
+            ApiError::Unknown(_)
+        ));
+    }
+
+    #[test]
+    fn rate_limited() {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::RETRY_AFTER, HeaderValue::from_static("30"));
+        for status in [420, 429] {
+            let status = StatusCode::from_u16(status).unwrap();
+            match ApiError::<MockError>::from_response(
+                status,
+                &headers,
+                json!({ "message": "slow down" }),
+            ) {
+                ApiError::RateLimited { retry_after, body } => {
+                    assert_eq!(retry_after, Some(Duration::from_secs(30)));
+                    assert_eq!(body.status, status);
+                    assert_eq!(body.message, "slow down");
+                }
+                other => panic!("unexpected error for {}: {:?}", status, other),
+            }
+        }
+        assert!(matches!(
+            from_response(420, json!({ "message": "slow down" })),
+            ApiError::RateLimited {
+                retry_after: None,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn validation_errors() {
+        let error = from_response(
+            400,
+            json!({
+                "status": 400,
+                "message": "The submitted run is invalid.",
+                "errors": [
+                    "time is required",
+                    { "name": "video", "error": "must be a URL" },
+                ],
+            }),
+        );
+        let body = match &error {
+            ApiError::ValidationFailed(body) => body,
+            other => panic!("unexpected error: {:?}", other),
+        };
+        assert_eq!(
+            body.errors,
+            [
+                FieldError {
+                    field: None,
+                    message: "time is required".into(),
+                },
+                FieldError {
+                    field: Some("video".into()),
+                    message: "must be a URL".into(),
+                },
+            ]
+        );
+        assert_eq!(
+            error.to_string(),
+            "validation failed: The submitted run is invalid. (400 Bad Request); \
+             time is required; video: must be a URL"
+        );
+    }
+}
@@ -0,0 +1,467 @@
+//! The request pipeline shared by all queries.
+//!
+//! Every query goes through the same steps: the endpoint is turned into a
+//! [`PreparedRequest`], which is passed through
+//! [`RestClient::intercept_request`] and sent by the client. The response is
+//! passed through [`RestClient::intercept_response`], its status is checked,
+//! and the body is decoded. The hooks are called once per query, wrapping
+//! all attempts made by the client when a request is retried, and also for
+//! responses served from a cache.
+use bytes::Bytes;
+use http::{header, request::Builder as RequestBuilder, Request, Response};
+use log::debug;
+use serde::{de::DeserializeOwned, Deserialize};
+use serde_json::{json, Value};
+use url::Url;
+
+use crate::{
+    middleware::RequestInfo,
+    types::{Pagination, Root},
+};
+
+use super::{
+    endpoint::{Authenticated, Endpoint},
+    query::{self, RawResponse},
+    ApiError, AsyncClient, Client, RestClient,
+};
+
+/// The number of bytes of a non-JSON body kept in [`ApiError::NonJson`].
+const BODY_SNIPPET_LEN: usize = 256;
+
+/// A request built from an endpoint, ready to be sent.
+pub(crate) struct PreparedRequest {
+    url: Url,
+    request: RequestBuilder,
+    body: Vec<u8>,
+}
+
+impl PreparedRequest {
+    /// Build the request for an endpoint, sent to the endpoint's URL.
+    pub(crate) fn new<E, C>(endpoint: &E, client: &C) -> Result<Self, ApiError<C::Error>>
+    where
+        E: Endpoint + ?Sized,
+        C: RestClient,
+    {
+        let mut url = client.rest_endpoint(&endpoint.endpoint())?;
+        endpoint.set_query_parameters(&mut url)?;
+        Self::with_url(endpoint, client, url)
+    }
+
+    /// Build the request for an endpoint, sent to `url`.
+    pub(crate) fn with_url<E, C>(
+        endpoint: &E,
+        client: &C,
+        url: Url,
+    ) -> Result<Self, ApiError<C::Error>>
+    where
+        E: Endpoint + ?Sized,
+        C: RestClient,
+    {
+        if endpoint.requires_authentication() && !client.has_api_key() {
+            return Err(ApiError::RequiresAuthentication);
+        }
+        let mut request = Request::builder()
+            .method(endpoint.method())
+            .uri(query::url_to_http_uri(url.clone()));
+        if endpoint.requires_authentication() {
+            request = request.extension(Authenticated);
+        }
+        let (request, body) = match endpoint.body()? {
+            Some((mime, body)) => (request.header(header::CONTENT_TYPE, mime), body),
+            None => (request, Vec::new()),
+        };
+        Ok(Self { url, request, body })
+    }
+
+    /// Send the request using a synchronous client.
+    pub(crate) fn send<C>(self, client: &C) -> Result<SentRequest, ApiError<C::Error>>
+    where
+        C: Client,
+    {
+        debug!("{} {}", method(&self.request), self.url);
+        let url = self.url.clone();
+        let (request, body, info) = self.intercept(client)?;
+        let response = client.rest(request, body)?;
+        Self::received(client, url, &info, response)
+    }
+
+    /// Send the request using an asynchronous client.
+    pub(crate) async fn send_async<C>(self, client: &C) -> Result<SentRequest, ApiError<C::Error>>
+    where
+        C: AsyncClient + Sync,
+    {
+        debug!("{} {}", method(&self.request), self.url);
+        let url = self.url.clone();
+        let (request, body, info) = self.intercept(client)?;
+        let response = client.rest_async(request, body).await?;
+        Self::received(client, url, &info, response)
+    }
+
+    /// Pass the request through [`RestClient::intercept_request`].
+    fn intercept<C>(
+        self,
+        client: &C,
+    ) -> Result<(RequestBuilder, Vec<u8>, RequestInfo), ApiError<C::Error>>
+    where
+        C: RestClient,
+    {
+        let mut request = self.request.body(self.body)?;
+        client.intercept_request(&mut request)?;
+        let info = RequestInfo::new(&request);
+
+        let (parts, body) = request.into_parts();
+        let mut builder = Request::builder()
+            .method(parts.method)
+            .uri(parts.uri)
+            .version(parts.version);
+        if let Some(headers) = builder.headers_mut() {
+            *headers = parts.headers;
+        }
+        if let Some(extensions) = builder.extensions_mut() {
+            *extensions = parts.extensions;
+        }
+        Ok((builder, body, info))
+    }
+
+    fn received<C>(
+        client: &C,
+        url: Url,
+        info: &RequestInfo,
+        mut response: Response<Bytes>,
+    ) -> Result<SentRequest, ApiError<C::Error>>
+    where
+        C: RestClient,
+    {
+        debug!("{} {}", response.status(), url);
+        client.intercept_response(info, &mut response)?;
+        Ok(SentRequest { url, response })
+    }
+}
+
+/// A request that was sent, along with its response.
+pub(crate) struct SentRequest {
+    url: Url,
+    response: Response<Bytes>,
+}
+
+impl SentRequest {
+    /// Only check the status of the response.
+    pub(crate) fn ignore<E>(self) -> Result<(), ApiError<E>>
+    where
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        self.value().map(|_| ())
+    }
+
+    /// Decode the `data` of the response. Empty responses are deserialized
+    /// from `null`, so they can be returned as `()` or `Option<T>`.
+    pub(crate) fn data<T, E>(self) -> Result<T, ApiError<E>>
+    where
+        T: DeserializeOwned,
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        let value = self.value()?;
+        if value.is_null() {
+            return T::deserialize(value).map_err(ApiError::data_type::<T>);
+        }
+        serde_json::from_value::<Root<T>>(value)
+            .map(|root| root.data)
+            .map_err(ApiError::data_type::<T>)
+    }
+
+    /// Decode a page of results.
+    pub(crate) fn page<T, E>(self) -> Result<(Vec<T>, Pagination), ApiError<E>>
+    where
+        T: DeserializeOwned,
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        serde_json::from_value::<Root<Vec<T>>>(self.value()?)
+            .map(|root| (root.data, root.pagination.unwrap_or_default()))
+            .map_err(ApiError::data_type::<Vec<T>>)
+    }
+
+    /// Return the response with its data left untyped.
+    pub(crate) fn raw<E>(self) -> Result<RawResponse, ApiError<E>>
+    where
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        let value = response_value(&self.response)?;
+        let root = if value.is_null() {
+            Root {
+                data: Value::Null,
+                pagination: None,
+            }
+        } else {
+            Root::deserialize(value).map_err(ApiError::data_type::<Root<Value>>)?
+        };
+        let (parts, _) = self.response.into_parts();
+        Ok(RawResponse {
+            url: self.url,
+            status: parts.status,
+            headers: parts.headers,
+            root,
+        })
+    }
+
+    fn value<E>(&self) -> Result<Value, ApiError<E>>
+    where
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        response_value(&self.response)
+    }
+}
+
+/// Parse the body of a response, checking its status first.
+///
+/// Successful responses without a body are returned as `null`. Unsuccessful
+/// responses are turned into the matching [`ApiError`], and bodies that are
+/// not JSON into [`ApiError::NonJson`].
+fn response_value<E>(rsp: &Response<Bytes>) -> Result<Value, ApiError<E>>
+where
+    E: std::error::Error + Send + Sync + 'static,
+{
+    let status = rsp.status();
+    let body = rsp.body();
+    if body.iter().all(u8::is_ascii_whitespace) {
+        if status.is_success() {
+            return Ok(Value::Null);
+        }
+        let message = status.canonical_reason().unwrap_or_default();
+        return Err(ApiError::from_response(
+            status,
+            rsp.headers(),
+            json!({ "message": message }),
+        ));
+    }
+
+    let content_type = rsp
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map(ToOwned::to_owned);
+    match serde_json::from_slice(body) {
+        Ok(value) if status.is_success() => Ok(value),
+        Ok(value) => Err(ApiError::from_response(status, rsp.headers(), value)),
+        // Only report a JSON error if the server claimed to send JSON.
+        Err(source)
+            if status.is_success()
+                && content_type
+                    .as_deref()
+                    .is_none_or(|content_type| content_type.contains("json")) =>
+        {
+            Err(ApiError::Json(source))
+        }
+        Err(_) => Err(ApiError::NonJson {
+            status,
+            content_type,
+            body: snippet(body),
+        }),
+    }
+}
+
+fn snippet(body: &[u8]) -> String {
+    let end = body.len().min(BODY_SNIPPET_LEN);
+    let mut snippet = String::from_utf8_lossy(&body[..end]).trim().to_owned();
+    if end < body.len() {
+        snippet.push('…');
+    }
+    snippet
+}
+
+fn method(request: &RequestBuilder) -> &str {
+    request.method_ref().map_or("GET", http::Method::as_str)
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+    use http::{header, Method, Response, StatusCode};
+    use serde_json::json;
+
+    use crate::{
+        api::{
+            endpoint::Authenticated, games::Game, ignore, runs::DeleteRun, ApiError, Client, Query,
+            RestClient,
+        },
+        middleware::RequestInfo,
+        mock::{MockClient, MockError},
+        test_utils::game_json,
+        types,
+    };
+
+    fn game() -> Game<'static> {
+        Game::builder().id("o1y9wo6q").build().unwrap()
+    }
+
+    fn response(status: StatusCode, content_type: &str, body: &str) -> Response<Bytes> {
+        Response::builder()
+            .status(status)
+            .header(header::CONTENT_TYPE, content_type)
+            .body(Bytes::from(body.to_owned()))
+            .unwrap()
+    }
+
+    fn query(client: &MockClient) -> Result<types::Game<'static>, ApiError<MockError>> {
+        game().query(client)
+    }
+
+    #[test]
+    fn decodes_data() {
+        let client = MockClient::new();
+        client.add_data(Method::GET, "games/o1y9wo6q", game_json("o1y9wo6q"));
+        assert_eq!(query(&client).unwrap().id, "o1y9wo6q".into());
+    }
+
+    #[test]
+    fn empty_bodies() {
+        let client = MockClient::new().authenticated(true);
+        client.add(
+            Method::DELETE,
+            "runs/deleted",
+            response(StatusCode::NO_CONTENT, "application/json", ""),
+        );
+        let delete = DeleteRun::builder().id("deleted").build().unwrap();
+        ignore(delete).query(&client).unwrap();
+
+        client.add(
+            Method::GET,
+            "games/o1y9wo6q",
+            response(StatusCode::NOT_FOUND, "application/json", "  \n"),
+        );
+        match query(&client) {
+            Err(ApiError::NotFound(body)) => {
+                assert_eq!(body.status, StatusCode::NOT_FOUND);
+                assert_eq!(body.message, "Not Found");
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn status_is_checked_before_decoding() {
+        let client = MockClient::new();
+        client.add_json(
+            Method::GET,
+            "games/o1y9wo6q",
+            StatusCode::FORBIDDEN,
+            json!({ "status": 403, "message": "no access" }),
+        );
+        match query(&client) {
+            Err(ApiError::Forbidden(body)) => assert_eq!(body.message, "no access"),
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn non_json_bodies() {
+        let client = MockClient::new();
+        let page = format!("<html>{}</html>", "x".repeat(300));
+        client.add(
+            Method::GET,
+            "games/o1y9wo6q",
+            response(StatusCode::BAD_GATEWAY, "text/html", &page),
+        );
+        match query(&client) {
+            Err(ApiError::NonJson {
+                status,
+                content_type,
+                body,
+            }) => {
+                assert_eq!(status, StatusCode::BAD_GATEWAY);
+                assert_eq!(content_type.as_deref(), Some("text/html"));
+                assert!(body.starts_with("<html>xxx"));
+                assert!(body.ends_with('…'));
+                assert_eq!(body.chars().count(), 257);
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+
+        // A successful response that is not JSON comes from a proxy, not the
+        // API.
+        client.add(
+            Method::GET,
+            "games/o1y9wo6q",
+            response(StatusCode::OK, "text/html", "<html></html>"),
+        );
+        assert!(matches!(
+            query(&client),
+            Err(ApiError::NonJson {
+                status: StatusCode::OK,
+                ..
+            })
+        ));
+
+        client.add(
+            Method::GET,
+            "games/o1y9wo6q",
+            response(StatusCode::OK, "application/json", "{\"data\":"),
+        );
+        assert!(matches!(query(&client), Err(ApiError::Json(_))));
+    }
+
+    /// Adds a header to requests, and rejects responses to requests that
+    /// lost the [`Authenticated`] marker on the way to the client.
+    struct Hooked(MockClient);
+
+    impl RestClient for Hooked {
+        type Error = MockError;
+
+        fn rest_endpoint(&self, endpoint: &str) -> Result<url::Url, ApiError<MockError>> {
+            self.0.rest_endpoint(endpoint)
+        }
+
+        fn has_api_key(&self) -> bool {
+            self.0.has_api_key()
+        }
+
+        fn intercept_request(
+            &self,
+            request: &mut http::Request<Vec<u8>>,
+        ) -> Result<(), ApiError<MockError>> {
+            request
+                .headers_mut()
+                .insert("x-hooked", http::HeaderValue::from_static("yes"));
+            Ok(())
+        }
+
+        fn intercept_response(
+            &self,
+            request: &RequestInfo,
+            response: &mut Response<Bytes>,
+        ) -> Result<(), ApiError<MockError>> {
+            assert_eq!(request.method(), Method::DELETE);
+            assert_eq!(request.headers()["x-hooked"], "yes");
+            *response.status_mut() = StatusCode::NO_CONTENT;
+            Ok(())
+        }
+    }
+
+    impl Client for Hooked {
+        fn rest(
+            &self,
+            request: http::request::Builder,
+            body: Vec<u8>,
+        ) -> Result<Response<Bytes>, ApiError<MockError>> {
+            assert!(request
+                .extensions_ref()
+                .is_some_and(|extensions| extensions.get::<Authenticated>().is_some()));
+            self.0.rest(request, body)
+        }
+    }
+
+    #[test]
+    fn hooks_see_every_query() {
+        let client = Hooked(MockClient::new().authenticated(true));
+        client.0.add(
+            Method::DELETE,
+            "runs/deleted",
+            response(StatusCode::INTERNAL_SERVER_ERROR, "application/json", ""),
+        );
+        let delete = DeleteRun::builder().id("deleted").build().unwrap();
+        ignore(delete).query(&client).unwrap();
+
+        let requests = client.0.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].headers["x-hooked"], "yes");
+    }
+}
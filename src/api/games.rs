@@ -2,22 +2,35 @@
 //!
 //! Endpoints available for games
 
-use std::{borrow::Cow, collections::BTreeSet, fmt::Display};
+use std::{borrow::Cow, collections::BTreeSet, error::Error, fmt::Display, io};
+
+#[cfg(feature = "async")]
+use futures::TryStreamExt;
 
 use http::Method;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
+#[cfg(feature = "async")]
+use tokio::io::{AsyncWrite, AsyncWriteExt};
 
 use super::{
     categories::CategoryEmbeds, developers::DeveloperId, endpoint::Endpoint, engines::EngineId,
     error::BodyError, gametypes::GameTypeId, genres::GenreId, leaderboards::LeaderboardEmbeds,
-    platforms::PlatformId, publishers::PublisherId, regions::RegionId, users::UserId,
-    CategoriesSorting, Direction, Pageable, VariablesSorting,
+    levels::LevelEmbeds, platforms::PlatformId, publishers::PublisherId, regions::RegionId,
+    users::UserId, ApiError, CategoriesSorting, Client, Direction, Pageable, PagedEndpointExt,
+    VariablesSorting,
 };
+#[cfg(feature = "async")]
+use super::{AsyncClient, PageOptions};
+use crate::types::BulkGame;
+
+/// Largest page size accepted in bulk mode.
+pub const BULK_MAX_PAGE_SIZE: usize = 1000;
 
 /// Embeds available for games
 ///
-/// NOTE: Embeds can be nested. That is not handled by this API.
+/// Embeds can be nested, e.g. [`GameEmbeds::CategoriesWith`] with
+/// [`CategoryEmbeds::Variables`] embeds `categories.variables`.
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub enum GameEmbeds {
     /// Embed all levels defined for the game.
@@ -42,6 +55,10 @@ pub enum GameEmbeds {
     Publishers,
     /// Embed all variables defined for the game.
     Variables,
+    /// Embed all levels, with a nested embed (`levels.<embed>`).
+    LevelsWith(LevelEmbeds),
+    /// Embed all categories, with a nested embed (`categories.<embed>`).
+    CategoriesWith(CategoryEmbeds),
 }
 
 /// Sorting options for games
@@ -67,12 +84,13 @@ pub enum GamesSorting {
 
 // Does this belong here?
 /// Sorting options for levels
-#[derive(Debug, Serialize, Clone, Copy)]
+#[derive(Debug, Serialize, Clone, Copy, Default)]
 #[serde(rename_all = "kebab-case")]
 pub enum LevelsSorting {
     /// Sorts alphanumerically by the level name
     Name,
     /// Sorts by the order defined by the game moderator (default)
+    #[default]
     Pos,
 }
 
@@ -100,6 +118,24 @@ pub enum GameDerivedGamesBuilderError {
     Inner(#[from] GamesBuilderError),
 }
 
+/// Errors that occur while exporting the game catalog with [`BulkGames`].
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum ExportError<E>
+where
+    E: Error + Send + Sync + 'static,
+{
+    /// Error retrieving the games.
+    #[error(transparent)]
+    Api(#[from] ApiError<E>),
+    /// Error serializing a game.
+    #[error("failed to serialize game: {0}")]
+    Json(#[from] serde_json::Error),
+    /// Error writing to the output.
+    #[error("failed to write games: {0}")]
+    Io(#[from] io::Error),
+}
+
 /// Represents a game ID
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 pub struct GameId<'a>(Cow<'a, str>);
@@ -156,7 +192,7 @@ pub struct Games<'a> {
     publisher: Option<PublisherId<'a>>,
     #[doc = r"Only return games moderated by the given user."]
     moderator: Option<UserId<'a>>,
-    #[doc = r"Enable bulk access."]
+    #[doc = r"Enable bulk access. See [`BulkGames`] for a typed bulk listing."]
     #[serde(rename = "_bulk")]
     bulk: Option<bool>,
     #[doc = r"Sorting options for results."]
@@ -169,6 +205,44 @@ pub struct Games<'a> {
     embed: BTreeSet<GameEmbeds>,
 }
 
+/// Retrieves a list of all games in bulk mode.
+///
+/// Bulk mode returns only the basic fields of every game (see
+/// [`BulkGame`]), and allows pages of up to [`BULK_MAX_PAGE_SIZE`] games.
+/// Iterating over this endpoint requests the largest page size by default.
+/// Embeds are not available in bulk mode.
+#[derive(Default, Debug, Builder, Serialize, Clone)]
+#[builder(default, setter(into, strip_option))]
+#[serde(rename_all = "kebab-case")]
+pub struct BulkGames<'a> {
+    #[doc = r"Performs a fuzzy search across game names and abbreviations."]
+    name: Option<Cow<'a, str>>,
+    #[doc = r"Perform an exact-match search for this abbreviation."]
+    abbreviation: Option<Cow<'a, str>>,
+    #[doc = r"Restrict results to games released in the given year."]
+    released: Option<i64>,
+    #[doc = r"Restrict results to the given game type."]
+    gametype: Option<GameTypeId<'a>>,
+    #[doc = r"Restrict results to the given platform."]
+    platform: Option<PlatformId<'a>>,
+    #[doc = r"Restrict results to the given region."]
+    region: Option<RegionId<'a>>,
+    #[doc = r"Restrict results to the given genre."]
+    genre: Option<GenreId<'a>>,
+    #[doc = r"Restrict results to the given engine."]
+    engine: Option<EngineId<'a>>,
+    #[doc = r"Restrict results to the given developer."]
+    developer: Option<DeveloperId<'a>>,
+    #[doc = r"Restrict results to the given publisher."]
+    publisher: Option<PublisherId<'a>>,
+    #[doc = r"Only return games moderated by the given user."]
+    moderator: Option<UserId<'a>>,
+    #[doc = r"Sorting options for results."]
+    orderby: Option<GamesSorting>,
+    #[doc = r"Sort direction."]
+    direction: Option<Direction>,
+}
+
 /// Retrieves a single game, identified by ID.
 #[derive(Debug, Builder, Clone)]
 #[builder(setter(into, strip_option))]
@@ -313,6 +387,63 @@ impl<'a> GamesBuilder<'a> {
     }
 }
 
+impl<'a> BulkGames<'a> {
+    /// Create a builder for this endpoint.
+    pub fn builder() -> BulkGamesBuilder<'a> {
+        BulkGamesBuilder::default()
+    }
+
+    /// Write every game matching this endpoint to `writer` as JSON Lines, one
+    /// [`BulkGame`] per line. Returns the number of games written.
+    pub fn write_json_lines<C, W>(
+        &self,
+        client: &C,
+        mut writer: W,
+    ) -> Result<usize, ExportError<C::Error>>
+    where
+        C: Client,
+        W: io::Write,
+    {
+        let mut count = 0;
+        for game in self.iter(client) {
+            let game: BulkGame<'_> = game?;
+            serde_json::to_writer(&mut writer, &game)?;
+            writer.write_all(b"\n")?;
+            count += 1;
+        }
+        writer.flush()?;
+        Ok(count)
+    }
+
+    /// Write every game matching this endpoint to `writer` as JSON Lines, one
+    /// [`BulkGame`] per line. Returns the number of games written.
+    ///
+    /// Up to `prefetch` pages are requested ahead of the page being written.
+    #[cfg(feature = "async")]
+    pub async fn write_json_lines_async<C, W>(
+        &self,
+        client: &C,
+        prefetch: usize,
+        mut writer: W,
+    ) -> Result<usize, ExportError<C::Error>>
+    where
+        C: AsyncClient + Sync,
+        W: AsyncWrite + Unpin,
+    {
+        let mut count = 0;
+        let options = PageOptions::new().prefetch(prefetch);
+        let mut games = self.stream_with::<BulkGame<'static>, _>(client, options);
+        while let Some(game) = games.try_next().await? {
+            let mut line = serde_json::to_vec(&game)?;
+            line.push(b'\n');
+            writer.write_all(&line).await?;
+            count += 1;
+        }
+        writer.flush().await?;
+        Ok(count)
+    }
+}
+
 impl<'a> Game<'a> {
     /// Create a builder for this endpoint.
     pub fn builder() -> GameBuilder<'a> {
@@ -527,29 +658,25 @@ impl<'a> GameRecordsBuilder<'a> {
 }
 
 impl GameEmbeds {
-    fn as_str(&self) -> &'static str {
+    pub(crate) fn path(&self) -> Cow<'static, str> {
         match self {
-            GameEmbeds::Levels => "levels",
-            GameEmbeds::Categories => "categories",
-            GameEmbeds::Moderators => "moderators",
-            GameEmbeds::Gametypes => "gametypes",
-            GameEmbeds::Platforms => "platforms",
-            GameEmbeds::Regions => "regions",
-            GameEmbeds::Genres => "genres",
-            GameEmbeds::Engines => "engines",
-            GameEmbeds::Developers => "developers",
-            GameEmbeds::Publishers => "publishers",
-            GameEmbeds::Variables => "variables",
+            GameEmbeds::Levels => "levels".into(),
+            GameEmbeds::Categories => "categories".into(),
+            GameEmbeds::Moderators => "moderators".into(),
+            GameEmbeds::Gametypes => "gametypes".into(),
+            GameEmbeds::Platforms => "platforms".into(),
+            GameEmbeds::Regions => "regions".into(),
+            GameEmbeds::Genres => "genres".into(),
+            GameEmbeds::Engines => "engines".into(),
+            GameEmbeds::Developers => "developers".into(),
+            GameEmbeds::Publishers => "publishers".into(),
+            GameEmbeds::Variables => "variables".into(),
+            GameEmbeds::LevelsWith(embed) => format!("levels.{}", embed.path()).into(),
+            GameEmbeds::CategoriesWith(embed) => format!("categories.{}", embed.path()).into(),
         }
     }
 }
 
-impl Default for LevelsSorting {
-    fn default() -> Self {
-        Self::Pos
-    }
-}
-
 impl Endpoint for Games<'_> {
     fn method(&self) -> http::Method {
         Method::GET
@@ -602,6 +729,25 @@ impl Endpoint for GameLevels<'_> {
     }
 }
 
+impl Endpoint for BulkGames<'_> {
+    fn method(&self) -> http::Method {
+        Method::GET
+    }
+
+    fn endpoint(&self) -> Cow<'static, str> {
+        "games".into()
+    }
+
+    fn query_parameters(&self) -> Result<Cow<'static, str>, BodyError> {
+        let params = serde_urlencoded::to_string(self)?;
+        if params.is_empty() {
+            Ok("_bulk=yes".into())
+        } else {
+            Ok(format!("{}&_bulk=yes", params).into())
+        }
+    }
+}
+
 impl Endpoint for GameVariables<'_> {
     fn method(&self) -> Method {
         Method::GET
@@ -644,9 +790,9 @@ impl Endpoint for GameRecords<'_> {
     }
 }
 
-impl From<&GameEmbeds> for &'static str {
+impl From<&GameEmbeds> for Cow<'static, str> {
     fn from(value: &GameEmbeds) -> Self {
-        value.as_str()
+        value.path()
     }
 }
 
@@ -654,4 +800,77 @@ impl Pageable for GameDerivedGames<'_> {}
 
 impl Pageable for Games<'_> {}
 
+impl Pageable for BulkGames<'_> {
+    fn max_page_size(&self) -> usize {
+        BULK_MAX_PAGE_SIZE
+    }
+
+    fn default_page_size(&self) -> Option<usize> {
+        Some(BULK_MAX_PAGE_SIZE)
+    }
+}
+
 impl Pageable for GameRecords<'_> {}
+
+#[cfg(test)]
+mod tests {
+    use http::{Method, StatusCode};
+    use serde_json::json;
+
+    use super::*;
+    use crate::mock::MockClient;
+
+    fn client() -> MockClient {
+        let client = MockClient::new();
+        let game = |id: &str| {
+            json!({
+                "id": id,
+                "names": { "international": id, "japanese": null, "twitch": null },
+                "abbreviation": id,
+                "weblink": format!("https://www.speedrun.com/{}", id),
+            })
+        };
+        client.add_json(
+            Method::GET,
+            "games?_bulk=yes&offset=0&max=1000",
+            StatusCode::OK,
+            json!({
+                "data": [game("a"), game("b")],
+                "pagination": { "offset": 0, "max": 1000, "size": 2, "links": [] },
+            }),
+        );
+        client
+    }
+
+    fn bulk_games() -> BulkGames<'static> {
+        BulkGames::builder().build().unwrap()
+    }
+
+    #[test]
+    fn write_json_lines() {
+        let mut output = Vec::new();
+        let count = bulk_games()
+            .write_json_lines(&client(), &mut output)
+            .unwrap();
+        assert_eq!(count, 2);
+        let lines: Vec<BulkGame<'_>> = String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[1].abbreviation, "b");
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn write_json_lines_async() {
+        let mut output = Vec::new();
+        let count = bulk_games()
+            .write_json_lines_async(&client(), 0, &mut output)
+            .await
+            .unwrap();
+        assert_eq!(count, 2);
+        assert_eq!(String::from_utf8(output).unwrap().lines().count(), 2);
+    }
+}
@@ -10,10 +10,11 @@ use serde::{Deserialize, Serialize};
 use super::{endpoint::Endpoint, Direction, Pageable};
 
 /// Sorting options for game type
-#[derive(Debug, Serialize, Clone, Copy)]
+#[derive(Debug, Serialize, Clone, Copy, Default)]
 #[serde(rename_all = "kebab-case")]
 pub enum GameTypesSorting {
     /// Sort alphanumerically by game type name (default)
+    #[default]
     Name,
 }
 
@@ -79,12 +80,6 @@ impl<'a> GameType<'a> {
     }
 }
 
-impl Default for GameTypesSorting {
-    fn default() -> Self {
-        Self::Name
-    }
-}
-
 impl Endpoint for GameTypes {
     fn method(&self) -> http::Method {
         Method::GET
@@ -10,10 +10,11 @@ use serde::{Deserialize, Serialize};
 use super::{endpoint::Endpoint, Direction, Pageable};
 
 /// Sorting options for genre
-#[derive(Debug, Serialize, Clone, Copy)]
+#[derive(Debug, Serialize, Clone, Copy, Default)]
 #[serde(rename_all = "kebab-case")]
 pub enum GenresSorting {
     /// Sort alphanumerically by genre name (default)
+    #[default]
     Name,
 }
 
@@ -79,12 +80,6 @@ impl<'a> Genre<'a> {
     }
 }
 
-impl Default for GenresSorting {
-    fn default() -> Self {
-        Self::Name
-    }
-}
-
 impl Endpoint for Genres {
     fn method(&self) -> http::Method {
         Method::GET
@@ -0,0 +1,51 @@
+use async_trait::async_trait;
+
+use super::{
+    endpoint::Endpoint,
+    executor::PreparedRequest,
+    query::{AsyncQuery, Query},
+    ApiError, AsyncClient, Client,
+};
+
+/// A query modifier that ignores the data returned by an endpoint.
+///
+/// Only the status of the response is checked, so this works for endpoints
+/// that return an empty body (e.g. [`DeleteRun`]) as well as endpoints whose
+/// data is not needed.
+///
+/// [`DeleteRun`]: super::runs::DeleteRun
+#[derive(Debug, Clone)]
+pub struct Ignore<E> {
+    endpoint: E,
+}
+
+/// Ignore the data returned by an endpoint.
+pub fn ignore<E>(endpoint: E) -> Ignore<E> {
+    Ignore { endpoint }
+}
+
+impl<E, C> Query<(), C> for Ignore<E>
+where
+    E: Endpoint,
+    C: Client,
+{
+    fn query(&self, client: &C) -> Result<(), ApiError<C::Error>> {
+        PreparedRequest::new(&self.endpoint, client)?
+            .send(client)?
+            .ignore()
+    }
+}
+
+#[async_trait]
+impl<E, C> AsyncQuery<(), C> for Ignore<E>
+where
+    E: Endpoint + Sync,
+    C: AsyncClient + Sync,
+{
+    async fn query_async(&self, client: &C) -> Result<(), ApiError<C::Error>> {
+        PreparedRequest::new(&self.endpoint, client)?
+            .send_async(client)
+            .await?
+            .ignore()
+    }
+}
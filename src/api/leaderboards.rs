@@ -12,13 +12,13 @@ use serde::Serialize;
 
 use crate::{
     api::{endpoint::Endpoint, error::BodyError},
-    types::TimingMethod,
+    types::{Date, TimingMethod},
 };
 
 use super::{
-    categories::CategoryId,
-    games::GameId,
-    levels::LevelId,
+    categories::{CategoryEmbeds, CategoryId},
+    games::{GameEmbeds, GameId},
+    levels::{LevelEmbeds, LevelId},
     platforms::PlatformId,
     regions::RegionId,
     variables::{ValueId, VariableId},
@@ -26,7 +26,8 @@ use super::{
 
 /// Embeds available for leaderboards.
 ///
-/// NOTE: Embeds can be nested. That is not handled by this API.
+/// Embeds can be nested, e.g. [`LeaderboardEmbeds::CategoryWith`] with
+/// [`CategoryEmbeds::Variables`] embeds `category.variables`.
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub enum LeaderboardEmbeds {
     /// Embed the full game resource.
@@ -44,6 +45,12 @@ pub enum LeaderboardEmbeds {
     Platforms,
     /// Adds all applicable variables for the chosen level/categories
     Variables,
+    /// Embed the full game resource, with a nested embed (`game.<embed>`).
+    GameWith(GameEmbeds),
+    /// Embed the category, with a nested embed (`category.<embed>`).
+    CategoryWith(CategoryEmbeds),
+    /// Embed the level, with a nested embed (`level.<embed>`).
+    LevelWith(LevelEmbeds),
 }
 
 /// Retrieves a full-game leaderboard identified by game and category.
@@ -77,9 +84,9 @@ pub struct FullGameLeaderboard<'a> {
     #[doc = r"What [`TimingMethod`] to use to determine the sorting of runs."]
     #[builder(default)]
     timing: Option<TimingMethod>,
-    #[doc = r"Only return runs done on or before this date. [ISO 8601 date string](https://en.wikipedia.org/wiki/ISO_8601#Dates)."]
+    #[doc = r"Only return runs done on or before this date."]
     #[builder(default)]
-    date: Option<String>,
+    date: Option<Date>,
     #[builder(setter(name = "_variables"), private, default)]
     #[serde(skip)]
     variables: HashMap<VariableId<'a>, ValueId<'a>>,
@@ -124,9 +131,9 @@ pub struct IndividualLevelLeaderboard<'a> {
     #[doc = r"What [`TimingMethod`] to use to determine the sorting of runs."]
     #[builder(default)]
     timing: Option<TimingMethod>,
-    #[doc = r"Only return runs done on or before this date. [ISO 8601 date string](https://en.wikipedia.org/wiki/ISO_8601#Dates)."]
+    #[doc = r"Only return runs done on or before this date."]
     #[builder(default)]
-    date: Option<String>,
+    date: Option<Date>,
     #[builder(setter(name = "_variables"), private, default)]
     #[serde(skip)]
     variables: HashMap<VariableId<'a>, ValueId<'a>>,
@@ -235,15 +242,64 @@ impl<'a> IndividualLevelLeaderboardBuilder<'a> {
 }
 
 impl LeaderboardEmbeds {
-    fn as_str(&self) -> &'static str {
+    fn path(&self) -> Cow<'static, str> {
         match self {
-            LeaderboardEmbeds::Game => "game",
-            LeaderboardEmbeds::Category => "category",
-            LeaderboardEmbeds::Level => "level",
-            LeaderboardEmbeds::Players => "players",
-            LeaderboardEmbeds::Regions => "regions",
-            LeaderboardEmbeds::Platforms => "platforms",
-            LeaderboardEmbeds::Variables => "variables",
+            LeaderboardEmbeds::Game => "game".into(),
+            LeaderboardEmbeds::Category => "category".into(),
+            LeaderboardEmbeds::Level => "level".into(),
+            LeaderboardEmbeds::Players => "players".into(),
+            LeaderboardEmbeds::Regions => "regions".into(),
+            LeaderboardEmbeds::Platforms => "platforms".into(),
+            LeaderboardEmbeds::Variables => "variables".into(),
+            LeaderboardEmbeds::GameWith(embed) => format!("game.{}", embed.path()).into(),
+            LeaderboardEmbeds::CategoryWith(embed) => format!("category.{}", embed.path()).into(),
+            LeaderboardEmbeds::LevelWith(embed) => format!("level.{}", embed.path()).into(),
+        }
+    }
+}
+
+/// Either a full-game or an individual level leaderboard.
+///
+/// Returned by [`types::Run::leaderboard`] for the leaderboard a run belongs
+/// to.
+///
+/// [`types::Run::leaderboard`]: crate::types::Run::leaderboard
+#[derive(Debug, Clone)]
+pub enum Leaderboard<'a> {
+    /// A full-game leaderboard.
+    FullGame(FullGameLeaderboard<'a>),
+    /// An individual level leaderboard.
+    IndividualLevel(IndividualLevelLeaderboard<'a>),
+}
+
+impl<'a> From<FullGameLeaderboard<'a>> for Leaderboard<'a> {
+    fn from(value: FullGameLeaderboard<'a>) -> Self {
+        Self::FullGame(value)
+    }
+}
+
+impl<'a> From<IndividualLevelLeaderboard<'a>> for Leaderboard<'a> {
+    fn from(value: IndividualLevelLeaderboard<'a>) -> Self {
+        Self::IndividualLevel(value)
+    }
+}
+
+impl Endpoint for Leaderboard<'_> {
+    fn method(&self) -> Method {
+        Method::GET
+    }
+
+    fn endpoint(&self) -> Cow<'static, str> {
+        match self {
+            Leaderboard::FullGame(leaderboard) => leaderboard.endpoint(),
+            Leaderboard::IndividualLevel(leaderboard) => leaderboard.endpoint(),
+        }
+    }
+
+    fn query_parameters(&self) -> Result<Cow<'static, str>, BodyError> {
+        match self {
+            Leaderboard::FullGame(leaderboard) => leaderboard.query_parameters(),
+            Leaderboard::IndividualLevel(leaderboard) => leaderboard.query_parameters(),
         }
     }
 }
@@ -304,8 +360,8 @@ impl Endpoint for IndividualLevelLeaderboard<'_> {
     }
 }
 
-impl From<&LeaderboardEmbeds> for &'static str {
+impl From<&LeaderboardEmbeds> for Cow<'static, str> {
     fn from(value: &LeaderboardEmbeds) -> Self {
-        value.as_str()
+        value.path()
     }
 }
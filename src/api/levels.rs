@@ -7,17 +7,23 @@ use http::Method;
 use serde::{Deserialize, Serialize};
 
 use super::{
-    endpoint::Endpoint, leaderboards::LeaderboardEmbeds, CategoriesSorting, Direction, Pageable,
-    VariablesSorting,
+    categories::CategoryEmbeds, endpoint::Endpoint, leaderboards::LeaderboardEmbeds,
+    CategoriesSorting, Direction, Pageable, VariablesSorting,
 };
 
 /// Embeds available for levels.
+///
+/// Embeds can be nested, e.g. [`LevelEmbeds::CategoriesWith`] with
+/// [`CategoryEmbeds::Variables`] embeds `categories.variables`.
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub enum LevelEmbeds {
     /// Embed per-level categories applicable to the requested level.
     Categories,
     /// Embed the variables applicable to the requested level.
     Variables,
+    /// Embed per-level categories, with a nested embed
+    /// (`categories.<embed>`).
+    CategoriesWith(CategoryEmbeds),
 }
 
 /// Represents a level ID.
@@ -180,10 +186,11 @@ impl<'a> LevelRecordsBuilder<'a> {
 }
 
 impl LevelEmbeds {
-    fn as_str(&self) -> &'static str {
+    pub(crate) fn path(&self) -> Cow<'static, str> {
         match self {
-            LevelEmbeds::Categories => "categories",
-            LevelEmbeds::Variables => "variables",
+            LevelEmbeds::Categories => "categories".into(),
+            LevelEmbeds::Variables => "variables".into(),
+            LevelEmbeds::CategoriesWith(embed) => format!("categories.{}", embed.path()).into(),
         }
     }
 }
@@ -244,9 +251,9 @@ impl Endpoint for LevelRecords<'_> {
     }
 }
 
-impl From<&LevelEmbeds> for &'static str {
+impl From<&LevelEmbeds> for Cow<'static, str> {
     fn from(value: &LevelEmbeds) -> Self {
-        value.as_str()
+        value.path()
     }
 }
 
@@ -0,0 +1,743 @@
+//! # Links
+//!
+//! Following the links returned with every resource.
+//!
+//! [`LinkEndpoint`] queries the resource a link points to, and the [`Links`]
+//! trait adds accessors for common relations to the `links` of a resource.
+//! Links to paginated lists are followed with [`ListLinkEndpoint`].
+//! [`Link::resolve`] turns a link into the typed endpoint it points to.
+//!
+//! ```rust ,no_run
+//! use speedrun_api::{
+//!     api::{links::Links, runs::Run, Query},
+//!     types, SpeedrunApiBuilder,
+//! };
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = SpeedrunApiBuilder::new().build()?;
+//!
+//! let run: types::Run = Run::builder().id("90y6pm7e").build()?.query(&client)?;
+//! if let Some(endpoint) = run.links.game() {
+//!     let game: types::Game = endpoint.query(&client)?;
+//!     println!("{}", game.names.international);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! ```rust
+//! use speedrun_api::{api::links::LinkTarget, types::Link};
+//!
+//! let link = Link {
+//!     rel: "game".into(),
+//!     uri: "https://www.speedrun.com/api/v1/games/v1pxjz68".parse().unwrap(),
+//! };
+//! assert!(matches!(link.resolve(), LinkTarget::Game(_)));
+//! ```
+//!
+//! [`Link::resolve`]: crate::types::Link::resolve
+
+use std::borrow::Cow;
+
+use http::Method;
+use percent_encoding::percent_decode_str;
+use url::Url;
+
+use crate::types::Link;
+
+use super::{
+    categories, developers, endpoint::Endpoint, engines, error::BodyError, games, gametypes,
+    genres, guests, leaderboards, levels, platforms, publishers, regions, runs, series, users,
+    variables, Pageable,
+};
+
+/// Retrieves the resource a [`Link`] points to.
+///
+/// The result can be deserialized into the type matching the relation, e.g.
+/// [`types::Game`] for a `game` link. Links to paginated lists (like `runs`)
+/// are converted into a [`ListLinkEndpoint`] with [`LinkEndpoint::list`].
+///
+/// [`types::Game`]: crate::types::Game
+#[derive(Debug, Clone)]
+pub struct LinkEndpoint {
+    rel: String,
+    url: Url,
+}
+
+/// Retrieves the paginated list a [`Link`] points to.
+///
+/// Unlike [`LinkEndpoint`], this can be paginated with
+/// [`PagedEndpointExt`].
+///
+/// [`PagedEndpointExt`]: super::PagedEndpointExt
+#[derive(Debug, Clone)]
+pub struct ListLinkEndpoint(LinkEndpoint);
+
+/// Accessors for the relations in the `links` of a resource.
+///
+/// Every accessor returns `None` if the resource has no link with that
+/// relation.
+pub trait Links {
+    /// Returns the link with the given `rel`, if present.
+    fn link(&self, rel: &str) -> Option<&Link>;
+
+    /// Returns an endpoint for the link with the given `rel`.
+    fn follow(&self, rel: &str) -> Option<LinkEndpoint> {
+        self.link(rel).and_then(LinkEndpoint::new)
+    }
+
+    /// The resource itself (`self`).
+    fn self_link(&self) -> Option<LinkEndpoint> {
+        self.follow("self")
+    }
+
+    /// The game (`game`).
+    fn game(&self) -> Option<LinkEndpoint> {
+        self.follow("game")
+    }
+
+    /// The category (`category`).
+    fn category(&self) -> Option<LinkEndpoint> {
+        self.follow("category")
+    }
+
+    /// The level (`level`).
+    fn level(&self) -> Option<LinkEndpoint> {
+        self.follow("level")
+    }
+
+    /// The platform (`platform`).
+    fn platform(&self) -> Option<LinkEndpoint> {
+        self.follow("platform")
+    }
+
+    /// The region (`region`).
+    fn region(&self) -> Option<LinkEndpoint> {
+        self.follow("region")
+    }
+
+    /// The user who examined a run (`examiner`).
+    fn examiner(&self) -> Option<LinkEndpoint> {
+        self.follow("examiner")
+    }
+
+    /// The leaderboard (`leaderboard`).
+    fn leaderboard(&self) -> Option<LinkEndpoint> {
+        self.follow("leaderboard")
+    }
+
+    /// The list of runs (`runs`).
+    fn runs(&self) -> Option<ListLinkEndpoint> {
+        self.follow("runs")?.list()
+    }
+
+    /// The list of games (`games`).
+    fn games(&self) -> Option<ListLinkEndpoint> {
+        self.follow("games")?.list()
+    }
+
+    /// The categories (`categories`).
+    fn categories(&self) -> Option<LinkEndpoint> {
+        self.follow("categories")
+    }
+
+    /// The levels (`levels`).
+    fn levels(&self) -> Option<LinkEndpoint> {
+        self.follow("levels")
+    }
+
+    /// The variables (`variables`).
+    fn variables(&self) -> Option<LinkEndpoint> {
+        self.follow("variables")
+    }
+
+    /// The records (`records`).
+    fn records(&self) -> Option<ListLinkEndpoint> {
+        self.follow("records")?.list()
+    }
+
+    /// The personal bests of a user (`personal-bests`).
+    fn personal_bests(&self) -> Option<LinkEndpoint> {
+        self.follow("personal-bests")
+    }
+}
+
+/// The endpoint a link points to, returned by [`Link::resolve`].
+///
+/// Links to endpoints that are not covered by this crate, that use query
+/// parameters the endpoint does not support, or whose URL does not match
+/// their relation (e.g. a `game` link to a run), resolve to
+/// [`LinkTarget::Other`].
+///
+/// [`Link::resolve`]: crate::types::Link::resolve
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub enum LinkTarget {
+    /// `/categories/{id}`
+    Category(categories::Category<'static>),
+    /// `/categories/{id}/variables`
+    CategoryVariables(categories::CategoryVariables<'static>),
+    /// `/categories/{id}/records`
+    CategoryRecords(categories::CategoryRecords<'static>),
+    /// `/developers/{id}`
+    Developer(developers::Developer<'static>),
+    /// `/engines/{id}`
+    Engine(engines::Engine<'static>),
+    /// `/games`, with filters.
+    Games(games::Games<'static>),
+    /// `/games/{id}`
+    Game(games::Game<'static>),
+    /// `/games/{id}/categories`
+    GameCategories(games::GameCategories<'static>),
+    /// `/games/{id}/levels`
+    GameLevels(games::GameLevels<'static>),
+    /// `/games/{id}/variables`
+    GameVariables(games::GameVariables<'static>),
+    /// `/games/{id}/derived-games`
+    GameDerivedGames(games::GameDerivedGames<'static>),
+    /// `/games/{id}/records`
+    GameRecords(games::GameRecords<'static>),
+    /// `/gametypes/{id}`
+    GameType(gametypes::GameType<'static>),
+    /// `/genres/{id}`
+    Genre(genres::Genre<'static>),
+    /// `/guests/{name}`
+    Guest(guests::Guest<'static>),
+    /// `/leaderboards/{game}/category/{category}`
+    FullGameLeaderboard(leaderboards::FullGameLeaderboard<'static>),
+    /// `/leaderboards/{game}/level/{level}/{category}`
+    IndividualLevelLeaderboard(leaderboards::IndividualLevelLeaderboard<'static>),
+    /// `/levels/{id}`
+    Level(levels::Level<'static>),
+    /// `/levels/{id}/categories`
+    LevelCategories(levels::LevelCategories<'static>),
+    /// `/levels/{id}/variables`
+    LevelVariables(levels::LevelVariables<'static>),
+    /// `/levels/{id}/records`
+    LevelRecords(levels::LevelRecords<'static>),
+    /// `/platforms/{id}`
+    Platform(platforms::Platform<'static>),
+    /// `/publishers/{id}`
+    Publisher(publishers::Publisher<'static>),
+    /// `/regions/{id}`
+    Region(regions::Region<'static>),
+    /// `/runs`, with filters.
+    Runs(runs::Runs<'static>),
+    /// `/runs/{id}`
+    Run(runs::Run<'static>),
+    /// `/series/{id}`
+    Series(series::Series<'static>),
+    /// `/series/{id}/games`
+    SeriesGames(series::SeriesGames<'static>),
+    /// `/users/{id}`
+    User(users::User<'static>),
+    /// `/users/{id}/personal-bests`
+    UserPersonalBests(users::UserPersonalBests<'static>),
+    /// `/variables/{id}`
+    Variable(variables::Variable<'static>),
+    /// A link that could not be resolved to an endpoint.
+    Other(Url),
+}
+
+impl LinkTarget {
+    /// Resolve an API URL into the endpoint it points to.
+    pub fn from_url(url: &Url) -> Self {
+        resolve(url).unwrap_or_else(|| LinkTarget::Other(url.clone()))
+    }
+
+    /// Resolve a link with the relation `rel`. Relations this crate knows
+    /// about (e.g. `game`) only resolve to the endpoints they can point to;
+    /// other relations are resolved from the URL alone.
+    pub fn from_link(rel: &str, url: &Url) -> Self {
+        match resolve(url) {
+            Some(target) if target.matches_rel(rel) => target,
+            _ => LinkTarget::Other(url.clone()),
+        }
+    }
+
+    fn matches_rel(&self, rel: &str) -> bool {
+        use LinkTarget::*;
+
+        match rel {
+            "game" | "base-game" => matches!(self, Game(_)),
+            "games" | "derived-games" | "romhacks" => {
+                matches!(self, Games(_) | SeriesGames(_) | GameDerivedGames(_))
+            }
+            "category" => matches!(self, Category(_)),
+            "categories" => matches!(self, GameCategories(_) | LevelCategories(_)),
+            "level" => matches!(self, Level(_)),
+            "levels" => matches!(self, GameLevels(_)),
+            "variables" => matches!(
+                self,
+                GameVariables(_) | CategoryVariables(_) | LevelVariables(_)
+            ),
+            "records" => matches!(self, GameRecords(_) | CategoryRecords(_) | LevelRecords(_)),
+            "leaderboard" => matches!(self, FullGameLeaderboard(_) | IndividualLevelLeaderboard(_)),
+            "runs" => matches!(self, Runs(_)),
+            "run" => matches!(self, Run(_)),
+            "platform" => matches!(self, Platform(_)),
+            "region" => matches!(self, Region(_)),
+            "series" => matches!(self, Series(_)),
+            "user" | "examiner" => matches!(self, User(_)),
+            "guest" => matches!(self, Guest(_)),
+            "personal-bests" => matches!(self, UserPersonalBests(_)),
+            "variable" => matches!(self, Variable(_)),
+            _ => true,
+        }
+    }
+}
+
+impl LinkEndpoint {
+    /// Create an endpoint for `link`. Returns `None` if the link does not
+    /// point to the API (e.g. a link to a video).
+    pub fn new(link: &Link) -> Option<Self> {
+        if !link.uri.path().starts_with(API_PATH) {
+            return None;
+        }
+        Some(Self {
+            rel: link.rel.clone(),
+            url: link.uri.clone(),
+        })
+    }
+
+    /// The relation of the link this endpoint was created from.
+    pub fn rel(&self) -> &str {
+        &self.rel
+    }
+
+    /// Resolve this endpoint into a typed endpoint. See [`Link::resolve`].
+    ///
+    /// [`Link::resolve`]: crate::types::Link::resolve
+    pub fn target(&self) -> LinkTarget {
+        LinkTarget::from_link(&self.rel, &self.url)
+    }
+
+    /// Convert into an endpoint for a paginated list. Returns `None` if the
+    /// link does not point to a paginated list.
+    pub fn list(self) -> Option<ListLinkEndpoint> {
+        if is_list(&self.url) {
+            Some(ListLinkEndpoint(self))
+        } else {
+            None
+        }
+    }
+}
+
+impl ListLinkEndpoint {
+    /// The relation of the link this endpoint was created from.
+    pub fn rel(&self) -> &str {
+        self.0.rel()
+    }
+
+    /// Resolve this endpoint into a typed endpoint. See [`Link::resolve`].
+    ///
+    /// [`Link::resolve`]: crate::types::Link::resolve
+    pub fn target(&self) -> LinkTarget {
+        self.0.target()
+    }
+}
+
+impl From<ListLinkEndpoint> for LinkEndpoint {
+    fn from(value: ListLinkEndpoint) -> Self {
+        value.0
+    }
+}
+
+impl Links for [Link] {
+    fn link(&self, rel: &str) -> Option<&Link> {
+        self.iter().find(|link| link.rel == rel)
+    }
+}
+
+impl Links for Option<Vec<Link>> {
+    fn link(&self, rel: &str) -> Option<&Link> {
+        self.as_deref()?.link(rel)
+    }
+}
+
+impl Endpoint for LinkEndpoint {
+    fn method(&self) -> Method {
+        Method::GET
+    }
+
+    // The link is requested relative to the client's base URL, so links are
+    // followed against the same server (e.g. a mock) that returned them.
+    fn endpoint(&self) -> Cow<'static, str> {
+        self.url.path()[API_PATH.len()..].to_owned().into()
+    }
+
+    fn query_parameters(&self) -> Result<Cow<'static, str>, BodyError> {
+        Ok(self.url.query().unwrap_or_default().to_owned().into())
+    }
+}
+
+impl Endpoint for ListLinkEndpoint {
+    fn method(&self) -> Method {
+        self.0.method()
+    }
+
+    fn endpoint(&self) -> Cow<'static, str> {
+        self.0.endpoint()
+    }
+
+    fn query_parameters(&self) -> Result<Cow<'static, str>, BodyError> {
+        self.0.query_parameters()
+    }
+}
+
+impl Pageable for ListLinkEndpoint {}
+
+const API_PATH: &str = "/api/v1/";
+
+/// Returns `true` if `url` points to an endpoint that is paginated.
+fn is_list(url: &Url) -> bool {
+    let path = match url.path().strip_prefix(API_PATH) {
+        Some(path) => path.trim_end_matches('/'),
+        None => return false,
+    };
+    let segments: Vec<&str> = path.split('/').collect();
+    matches!(
+        segments.as_slice(),
+        ["developers"]
+            | ["engines"]
+            | ["games"]
+            | ["gametypes"]
+            | ["genres"]
+            | ["platforms"]
+            | ["publishers"]
+            | ["regions"]
+            | ["runs"]
+            | ["series"]
+            | ["users"]
+            | ["games", _, "derived-games"]
+            | ["games", _, "records"]
+            | ["categories", _, "records"]
+            | ["levels", _, "records"]
+            | ["series", _, "games"]
+    )
+}
+
+fn resolve(url: &Url) -> Option<LinkTarget> {
+    let path = url.path().strip_prefix(API_PATH)?;
+    let segments = path
+        .trim_end_matches('/')
+        .split('/')
+        .map(|segment| {
+            percent_decode_str(segment)
+                .decode_utf8()
+                .ok()
+                .map(Cow::into_owned)
+        })
+        .collect::<Option<Vec<String>>>()?;
+    let segments: Vec<&str> = segments.iter().map(String::as_str).collect();
+    let query: Vec<(String, String)> = url.query_pairs().into_owned().collect();
+
+    // Only the list and leaderboard endpoints take query parameters.
+    let target = match segments.as_slice() {
+        ["games"] => return games_filter(&query).map(LinkTarget::Games),
+        ["runs"] => return runs_filter(&query).map(LinkTarget::Runs),
+        ["leaderboards", game, "category", category] => {
+            let mut builder = leaderboards::FullGameLeaderboard::builder();
+            builder.game(owned(game)).category(owned(category));
+            builder.variables(leaderboard_variables(&query)?);
+            return builder.build().ok().map(LinkTarget::FullGameLeaderboard);
+        }
+        ["leaderboards", game, "level", level, category] => {
+            let mut builder = leaderboards::IndividualLevelLeaderboard::builder();
+            builder
+                .game(owned(game))
+                .level(owned(level))
+                .category(owned(category));
+            builder.variables(leaderboard_variables(&query)?);
+            return builder
+                .build()
+                .ok()
+                .map(LinkTarget::IndividualLevelLeaderboard);
+        }
+        _ if !query.is_empty() => return None,
+        ["categories", id] => {
+            LinkTarget::Category(categories::Category::builder().id(owned(id)).build().ok()?)
+        }
+        ["categories", id, "variables"] => LinkTarget::CategoryVariables(
+            categories::CategoryVariables::builder()
+                .id(owned(id))
+                .build()
+                .ok()?,
+        ),
+        ["categories", id, "records"] => LinkTarget::CategoryRecords(
+            categories::CategoryRecords::builder()
+                .id(owned(id))
+                .build()
+                .ok()?,
+        ),
+        ["developers", id] => LinkTarget::Developer(
+            developers::Developer::builder()
+                .id(owned(id))
+                .build()
+                .ok()?,
+        ),
+        ["engines", id] => {
+            LinkTarget::Engine(engines::Engine::builder().id(owned(id)).build().ok()?)
+        }
+        ["games", id] => LinkTarget::Game(games::Game::builder().id(owned(id)).build().ok()?),
+        ["games", id, "categories"] => LinkTarget::GameCategories(
+            games::GameCategories::builder()
+                .id(owned(id))
+                .build()
+                .ok()?,
+        ),
+        ["games", id, "levels"] => {
+            LinkTarget::GameLevels(games::GameLevels::builder().id(owned(id)).build().ok()?)
+        }
+        ["games", id, "variables"] => {
+            LinkTarget::GameVariables(games::GameVariables::builder().id(owned(id)).build().ok()?)
+        }
+        ["games", id, "derived-games"] => LinkTarget::GameDerivedGames(
+            games::GameDerivedGames::builder()
+                .id(owned(id))
+                .build()
+                .ok()?,
+        ),
+        ["games", id, "records"] => {
+            LinkTarget::GameRecords(games::GameRecords::builder().id(owned(id)).build().ok()?)
+        }
+        ["gametypes", id] => {
+            LinkTarget::GameType(gametypes::GameType::builder().id(owned(id)).build().ok()?)
+        }
+        ["genres", id] => LinkTarget::Genre(genres::Genre::builder().id(owned(id)).build().ok()?),
+        ["guests", name] => {
+            LinkTarget::Guest(guests::Guest::builder().name(owned(name)).build().ok()?)
+        }
+        ["levels", id] => LinkTarget::Level(levels::Level::builder().id(owned(id)).build().ok()?),
+        ["levels", id, "categories"] => LinkTarget::LevelCategories(
+            levels::LevelCategories::builder()
+                .id(owned(id))
+                .build()
+                .ok()?,
+        ),
+        ["levels", id, "variables"] => LinkTarget::LevelVariables(
+            levels::LevelVariables::builder()
+                .id(owned(id))
+                .build()
+                .ok()?,
+        ),
+        ["levels", id, "records"] => {
+            LinkTarget::LevelRecords(levels::LevelRecords::builder().id(owned(id)).build().ok()?)
+        }
+        ["platforms", id] => {
+            LinkTarget::Platform(platforms::Platform::builder().id(owned(id)).build().ok()?)
+        }
+        ["publishers", id] => LinkTarget::Publisher(
+            publishers::Publisher::builder()
+                .id(owned(id))
+                .build()
+                .ok()?,
+        ),
+        ["regions", id] => {
+            LinkTarget::Region(regions::Region::builder().id(owned(id)).build().ok()?)
+        }
+        ["runs", id] => LinkTarget::Run(runs::Run::builder().id(owned(id)).build().ok()?),
+        ["series", id] => LinkTarget::Series(series::Series::builder().id(owned(id)).build().ok()?),
+        ["series", id, "games"] => {
+            LinkTarget::SeriesGames(series::SeriesGames::builder().id(owned(id)).build().ok()?)
+        }
+        ["users", id] => LinkTarget::User(users::User::builder().id(owned(id)).build().ok()?),
+        ["users", id, "personal-bests"] => LinkTarget::UserPersonalBests(
+            users::UserPersonalBests::builder()
+                .id(owned(id))
+                .build()
+                .ok()?,
+        ),
+        ["variables", id] => {
+            LinkTarget::Variable(variables::Variable::builder().id(owned(id)).build().ok()?)
+        }
+        _ => return None,
+    };
+    Some(target)
+}
+
+fn owned(value: &str) -> String {
+    value.to_owned()
+}
+
+fn games_filter(query: &[(String, String)]) -> Option<games::Games<'static>> {
+    let mut builder = games::Games::builder();
+    for (key, value) in query {
+        let value = value.clone();
+        match key.as_str() {
+            "name" => builder.name(value),
+            "abbreviation" => builder.abbreviation(value),
+            "released" => builder.released(value.parse::<i64>().ok()?),
+            "gametype" => builder.gametype(value),
+            "platform" => builder.platform(value),
+            "region" => builder.region(value),
+            "genre" => builder.genre(value),
+            "engine" => builder.engine(value),
+            "developer" => builder.developer(value),
+            "publisher" => builder.publisher(value),
+            "moderator" => builder.moderator(value),
+            _ => return None,
+        };
+    }
+    builder.build().ok()
+}
+
+fn runs_filter(query: &[(String, String)]) -> Option<runs::Runs<'static>> {
+    let mut builder = runs::Runs::builder();
+    for (key, value) in query {
+        let value = value.clone();
+        match key.as_str() {
+            "user" => builder.user(value),
+            "guest" => builder.guest(value),
+            "examiner" => builder.examiner(value),
+            "game" => builder.game(value),
+            "level" => builder.level(value),
+            "category" => builder.category(value),
+            "platform" => builder.platform(value),
+            "region" => builder.region(value),
+            "emulated" => builder.emulated(value.parse::<bool>().ok()?),
+            _ => return None,
+        };
+    }
+    builder.build().ok()
+}
+
+fn leaderboard_variables(query: &[(String, String)]) -> Option<Vec<(String, String)>> {
+    query
+        .iter()
+        .map(|(key, value)| {
+            key.strip_prefix("var-")
+                .map(|variable| (variable.to_owned(), value.clone()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn link(rel: &str, uri: &str) -> Link {
+        Link {
+            rel: rel.into(),
+            uri: uri.parse().unwrap(),
+        }
+    }
+
+    #[test]
+    fn resolve_by_relation() {
+        let game = link("game", "https://www.speedrun.com/api/v1/games/v1pxjz68");
+        assert!(matches!(game.resolve(), LinkTarget::Game(_)));
+
+        let records = link(
+            "records",
+            "https://www.speedrun.com/api/v1/categories/wkpoo02r/records",
+        );
+        assert!(matches!(records.resolve(), LinkTarget::CategoryRecords(_)));
+
+        let leaderboard = link(
+            "leaderboard",
+            "https://www.speedrun.com/api/v1/leaderboards/o1y9wo6q/category/wkpoo02r?var-e8m7em86=9qj7z0oq",
+        );
+        assert!(matches!(
+            leaderboard.resolve(),
+            LinkTarget::FullGameLeaderboard(_)
+        ));
+    }
+
+    #[test]
+    fn mismatched_relation_is_not_resolved() {
+        let link = link("game", "https://www.speedrun.com/api/v1/runs/90y6pm7e");
+        assert!(matches!(link.resolve(), LinkTarget::Other(_)));
+        // The URL alone still resolves.
+        assert!(matches!(
+            LinkTarget::from_url(&link.uri),
+            LinkTarget::Run(_)
+        ));
+        assert!(matches!(
+            link.endpoint().unwrap().target(),
+            LinkTarget::Other(_)
+        ));
+    }
+
+    #[test]
+    fn unknown_relations_resolve_by_url() {
+        let link = link("self", "https://www.speedrun.com/api/v1/runs/90y6pm7e");
+        assert!(matches!(link.resolve(), LinkTarget::Run(_)));
+    }
+
+    #[test]
+    fn unsupported_links() {
+        let runs = link(
+            "runs",
+            "https://www.speedrun.com/api/v1/runs?game=o1y9wo6q&unknown=1",
+        );
+        assert!(matches!(runs.resolve(), LinkTarget::Other(_)));
+
+        let video = link("video", "https://www.youtube.com/watch?v=dQw4w9WgXcQ");
+        assert!(matches!(video.resolve(), LinkTarget::Other(_)));
+        assert!(video.endpoint().is_none());
+    }
+
+    #[test]
+    fn only_lists_are_paginated() {
+        let runs = link("runs", "https://www.speedrun.com/api/v1/runs?game=o1y9wo6q");
+        let list = runs.endpoint().unwrap().list().unwrap();
+        assert_eq!(list.rel(), "runs");
+        assert_eq!(list.query_parameters().unwrap(), "game=o1y9wo6q");
+
+        let records = link(
+            "records",
+            "https://www.speedrun.com/api/v1/games/o1y9wo6q/records",
+        );
+        assert!(records.endpoint().unwrap().list().is_some());
+
+        let game = link("game", "https://www.speedrun.com/api/v1/games/o1y9wo6q");
+        assert!(game.endpoint().unwrap().list().is_none());
+        let categories = link(
+            "categories",
+            "https://www.speedrun.com/api/v1/games/o1y9wo6q/categories",
+        );
+        assert!(categories.endpoint().unwrap().list().is_none());
+    }
+
+    #[test]
+    fn paginate_list_links() {
+        use crate::{api::PagedEndpointExt, mock::MockClient};
+
+        let client = MockClient::new();
+        client.add_json(
+            Method::GET,
+            "runs?game=o1y9wo6q&offset=0",
+            http::StatusCode::OK,
+            serde_json::json!({
+                "data": [{"id": "a"}, {"id": "b"}],
+                "pagination": {"offset": 0, "max": 20, "size": 2, "links": []},
+            }),
+        );
+
+        let runs = vec![link(
+            "runs",
+            "https://www.speedrun.com/api/v1/runs?game=o1y9wo6q",
+        )];
+        let ids: Vec<serde_json::Value> = runs
+            .as_slice()
+            .runs()
+            .unwrap()
+            .iter(&client)
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(ids.len(), 2);
+    }
+
+    #[test]
+    fn links_accessors() {
+        let links = vec![
+            link("self", "https://www.speedrun.com/api/v1/runs/90y6pm7e"),
+            link("game", "https://www.speedrun.com/api/v1/games/o1y9wo6q"),
+        ];
+        let game = links.as_slice().game().unwrap();
+        assert_eq!(game.rel(), "game");
+        assert_eq!(game.endpoint(), "games/o1y9wo6q");
+        assert!(links.as_slice().category().is_none());
+        assert!(None::<Vec<Link>>.game().is_none());
+    }
+}
@@ -7,9 +7,10 @@ use serde::Serialize;
 use super::{endpoint::Endpoint, Direction};
 
 /// Sorting options for notifications
-#[derive(Debug, Clone, Serialize, Copy)]
+#[derive(Debug, Clone, Serialize, Copy, Default)]
 pub enum NotificationsSorting {
     /// Sort by the date the notification was created (default)
+    #[default]
     Created,
 }
 
@@ -31,12 +32,6 @@ impl Notifications {
     }
 }
 
-impl Default for NotificationsSorting {
-    fn default() -> Self {
-        Self::Created
-    }
-}
-
 impl Endpoint for Notifications {
     fn method(&self) -> http::Method {
         Method::GET
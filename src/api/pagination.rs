@@ -1,21 +1,36 @@
 use async_trait::async_trait;
 use futures::{stream::BoxStream, StreamExt, TryStreamExt};
-use http::{header, Request};
 use serde::de::DeserializeOwned;
-use crate::types::Root;
 
 use crate::types::Pagination;
 
 use super::{
     ApiError,
     AsyncClient,
-    Client, endpoint::Endpoint, query::{self, AsyncQuery, Query}, RestClient,
+    Client, endpoint::Endpoint, executor::PreparedRequest,
+    query::{AsyncQuery, AsyncRawQuery, Query, RawQuery, RawResponse}, RestClient,
 };
 
-// TODO: Use provided "next" link for pagination
+/// Largest page size accepted by most paginated endpoints.
+pub const MAX_PAGE_SIZE: usize = 200;
+
+/// Page size used by the API when none is requested.
+const DEFAULT_PAGE_SIZE: usize = 20;
 
 /// Marker trait to indicate that an endpoint is pageable.
-pub trait Pageable {}
+pub trait Pageable {
+    /// Largest number of elements the endpoint returns per page.
+    fn max_page_size(&self) -> usize {
+        MAX_PAGE_SIZE
+    }
+
+    /// Number of elements per page requested when iterating over the
+    /// endpoint without an explicit page size. `None` uses the API default
+    /// of 20.
+    fn default_page_size(&self) -> Option<usize> {
+        None
+    }
+}
 
 /// Adapters specific to [`Pageable`] endpoints.
 pub trait PagedEndpointExt<'a, E> {
@@ -25,6 +40,16 @@ pub trait PagedEndpointExt<'a, E> {
         C: Client,
         T: DeserializeOwned;
 
+    /// Create an Iterator over the results of the paginated endpoint, using
+    /// the page size from `options`.
+    ///
+    /// Pages are always requested one after another, `options.prefetch` only
+    /// applies to streams.
+    fn iter_with<T, C>(&'a self, client: &'a C, options: PageOptions) -> PagedIter<'a, E, C, T>
+    where
+        C: Client,
+        T: DeserializeOwned;
+
     /// Retrieves a single page of results for the paginated endpoint.
     fn single_page(&'a self) -> SinglePageBuilder<'a, E>;
 
@@ -34,18 +59,135 @@ pub trait PagedEndpointExt<'a, E> {
         T: DeserializeOwned + Send + 'static,
         C: AsyncClient + Sync,
         E: Sync + Send;
+
+    /// Create an async Stream over the results of the paginated endpoint,
+    /// using the page size and prefetch depth from `options`.
+    fn stream_with<T, C>(
+        &'a self,
+        client: &'a C,
+        options: PageOptions,
+    ) -> BoxStream<'a, Result<T, ApiError<C::Error>>>
+    where
+        T: DeserializeOwned + Send + 'static,
+        C: AsyncClient + Sync,
+        E: Sync + Send;
+
+    /// Create an async Stream over the pages of the paginated endpoint.
+    ///
+    /// Every [`Page`] carries the pagination information returned by the API,
+    /// and the progress made so far.
+    fn stream_pages<T, C>(
+        &'a self,
+        client: &'a C,
+    ) -> BoxStream<'a, Result<Page<T>, ApiError<C::Error>>>
+    where
+        T: DeserializeOwned + Send + 'static,
+        C: AsyncClient + Sync,
+        E: Sync + Send;
+
+    /// Create an async Stream over the pages of the paginated endpoint, using
+    /// the page size and prefetch depth from `options`.
+    fn stream_pages_with<T, C>(
+        &'a self,
+        client: &'a C,
+        options: PageOptions,
+    ) -> BoxStream<'a, Result<Page<T>, ApiError<C::Error>>>
+    where
+        T: DeserializeOwned + Send + 'static,
+        C: AsyncClient + Sync,
+        E: Sync + Send;
+}
+
+/// Options for retrieving all pages of a paginated endpoint.
+///
+/// ```rust
+/// use speedrun_api::api::PageOptions;
+///
+/// // Request 200 runs at a time, keeping up to 4 more pages in flight.
+/// let options = PageOptions::new().page_size(200).prefetch(4);
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct PageOptions {
+    page_size: Option<usize>,
+    prefetch: usize,
+}
+
+impl PageOptions {
+    /// Create new options, using the default page size of the API and no
+    /// prefetching.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of elements per request. Valid values are between 1 and the
+    /// endpoint's [`Pageable::max_page_size`] (200 for most endpoints).
+    /// Invalid sizes are reported as [`ApiError::InvalidPageSize`].
+    pub fn page_size<T>(mut self, value: T) -> Self
+    where
+        T: Into<Option<usize>>,
+    {
+        self.page_size = value.into();
+        self
+    }
+
+    /// Number of pages requested ahead of the page currently being consumed
+    /// (default: 0).
+    ///
+    /// With prefetching, pages are requested by offset rather than by
+    /// following the `next` link of the previous page, so up to `prefetch`
+    /// requests past the last page are made and discarded.
+    pub fn prefetch(mut self, value: usize) -> Self {
+        self.prefetch = value;
+        self
+    }
+
+    /// Options with the largest page size of most endpoints, for helpers
+    /// that need every element of a list.
+    pub(crate) fn largest_pages() -> Self {
+        Self::new().page_size(MAX_PAGE_SIZE)
+    }
+
+    fn page_size_for<E: Pageable>(&self, paged: &E) -> Option<usize> {
+        self.page_size.or_else(|| paged.default_page_size())
+    }
 }
 
 /// Iterator type for the [`iter`] method on [`PagedEndpointExt`].
 ///
+/// Pages are requested by following the `next` link returned with every page,
+/// until a page without a `next` link is returned.
+///
 /// [`iter`]: PagedEndpointExt::iter
 pub struct PagedIter<'a, E, C, T> {
     client: &'a C,
     state: SinglePage<'a, E>,
     last_page: bool,
+    progress: PageProgress,
     current_page: Vec<T>,
 }
 
+/// Progress made while retrieving the pages of a paginated endpoint.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct PageProgress {
+    /// Number of pages retrieved so far.
+    pub pages: usize,
+    /// Number of elements retrieved so far.
+    pub items: usize,
+    /// `true` once the last page has been retrieved.
+    pub finished: bool,
+}
+
+/// A single page of results, returned by [`PagedEndpointExt::stream_pages`].
+#[derive(Debug, Clone)]
+pub struct Page<T> {
+    /// The elements on this page.
+    pub items: Vec<T>,
+    /// Pagination information returned with this page.
+    pub pagination: Pagination,
+    /// Progress made, including this page.
+    pub progress: PageProgress,
+}
+
 /// Builder for the [`SinglePage`] endpoint
 #[derive(Debug)]
 pub struct SinglePageBuilder<'a, E> {
@@ -60,21 +202,55 @@ pub struct SinglePage<'a, E> {
     inner: &'a E,
     offset: usize,
     max: Option<usize>,
+    link: Option<url::Url>,
 }
 
 impl<'a, E, C, T> PagedIter<'a, E, C, T>
 where
     E: Endpoint + Pageable,
 {
-    pub(crate) fn new(paged: &'a E, client: &'a C) -> Self {
-        let state = SinglePage::<E>::builder(paged).offset(0).build();
+    pub(crate) fn new(paged: &'a E, client: &'a C, options: PageOptions) -> Self {
+        let state = SinglePage::<E>::builder(paged)
+            .offset(0)
+            .page_size(options.page_size_for(paged))
+            .build();
         Self {
             client,
             state,
             last_page: false,
+            progress: PageProgress::default(),
             current_page: Vec::new(),
         }
     }
+
+    /// Progress made so far.
+    pub fn progress(&self) -> PageProgress {
+        self.progress
+    }
+
+    /// Total number of pages. Only known once the last page has been
+    /// retrieved, since the API does not report the total number of
+    /// elements.
+    pub fn total_pages(&self) -> Option<usize> {
+        self.progress.total_pages()
+    }
+}
+
+impl PageProgress {
+    /// Total number of pages, once the last page has been retrieved.
+    pub fn total_pages(&self) -> Option<usize> {
+        if self.finished {
+            Some(self.pages)
+        } else {
+            None
+        }
+    }
+
+    fn advance(&mut self, items: usize, last_page: bool) {
+        self.pages += 1;
+        self.items += items;
+        self.finished = last_page;
+    }
 }
 
 impl<'a, E> SinglePageBuilder<'a, E>
@@ -99,12 +275,14 @@ where
         self
     }
 
-    /// Number of elements per request. Valid values are between 1 and 200.
+    /// Number of elements per request. Valid values are between 1 and the
+    /// endpoint's [`Pageable::max_page_size`] (200 for most endpoints).
+    /// Invalid sizes are reported as [`ApiError::InvalidPageSize`] when the
+    /// page is queried.
     pub fn page_size<T>(mut self, value: T) -> Self
     where
         T: Into<Option<usize>>,
     {
-        // TODO: Validate that value is between 1 and 200.
         self.max = value.into();
         self
     }
@@ -118,6 +296,7 @@ where
             inner: self.inner,
             offset: self.offset.unwrap_or(0),
             max: self.max,
+            link: None,
         }
     }
 }
@@ -131,7 +310,16 @@ where
         SinglePageBuilder::new(paged)
     }
 
-    fn page_url<C: RestClient>(&self, client: &C) -> Result<url::Url, ApiError<C::Error>> {
+    fn prepare<C: RestClient>(&self, client: &C) -> Result<PreparedRequest, ApiError<C::Error>> {
+        if let Some(requested) = self.max {
+            let max = self.inner.max_page_size();
+            if requested == 0 || requested > max {
+                return Err(ApiError::InvalidPageSize { requested, max });
+            }
+        }
+        if let Some(link) = &self.link {
+            return PreparedRequest::with_url(self.inner, client, link.clone());
+        }
         let mut url = client.rest_endpoint(&self.inner.endpoint())?;
         self.inner.set_query_parameters(&mut url)?;
         {
@@ -141,7 +329,28 @@ where
                 pairs.append_pair("max", &format!("{}", max));
             }
         }
-        Ok(url)
+        PreparedRequest::with_url(self.inner, client, url)
+    }
+}
+
+impl<E, C> RawQuery<C> for SinglePage<'_, E>
+where
+    E: Endpoint + Pageable,
+    C: Client,
+{
+    fn query_raw(&self, client: &C) -> Result<RawResponse, ApiError<C::Error>> {
+        self.prepare(client)?.send(client)?.raw()
+    }
+}
+
+#[async_trait]
+impl<E, C> AsyncRawQuery<C> for SinglePage<'_, E>
+where
+    E: Endpoint + Pageable + Sync,
+    C: AsyncClient + Sync,
+{
+    async fn query_raw_async(&self, client: &C) -> Result<RawResponse, ApiError<C::Error>> {
+        self.prepare(client)?.send_async(client).await?.raw()
     }
 }
 
@@ -153,32 +362,7 @@ where
     E: Endpoint + Pageable + Sync,
 {
     async fn query_async(&self, client: &C) -> Result<(Vec<T>, Pagination), ApiError<C::Error>> {
-        let url = self.page_url(client)?;
-
-        let body = self.inner.body()?;
-
-        let req = Request::builder()
-            .method(self.inner.method())
-            .uri(query::url_to_http_uri(url));
-
-        let (req, data) = if let Some((mime, data)) = body.as_ref() {
-            let req = req.header(header::CONTENT_TYPE, *mime);
-            (req, data.clone())
-        } else {
-            (req, Vec::new())
-        };
-
-        let rsp = client.rest_async(req, data).await?;
-        let status = rsp.status();
-
-        let value = serde_json::from_slice(rsp.body())?;
-        if !status.is_success() {
-            return Err(ApiError::from_speedrun_api(value));
-        }
-
-        serde_json::from_value::<Root<Vec<T>>>(value)
-            .map(|value| (value.data, value.pagination.unwrap_or_default()))
-            .map_err(ApiError::data_type::<Vec<T>>)
+        self.prepare(client)?.send_async(client).await?.page()
     }
 }
 
@@ -195,16 +379,18 @@ where
             if self.last_page {
                 return None;
             }
-            self.current_page = match self.state.query(self.client) {
-                Ok((data, _pagination)) => data,
+            let (data, pagination) = match self.state.query(self.client) {
+                Ok(page) => page,
                 Err(err) => return Some(Err(err)),
             };
-            self.state.offset += self.current_page.len();
+            self.current_page = data;
 
-            // FIXME: 20 may not always be correct.
-            if self.current_page.len() < self.state.max.unwrap_or(20) {
-                self.last_page = true;
+            match next_link(&self.current_page, &pagination) {
+                Some(link) => self.state.link = Some(link),
+                None => self.last_page = true,
             }
+            self.progress
+                .advance(self.current_page.len(), self.last_page);
             self.current_page.reverse();
         }
 
@@ -221,45 +407,160 @@ where
         C: Client,
         T: DeserializeOwned,
     {
-        PagedIter::new(self, client)
+        PagedIter::new(self, client, PageOptions::default())
+    }
+
+    fn iter_with<T, C>(&'a self, client: &'a C, options: PageOptions) -> PagedIter<'a, E, C, T>
+    where
+        C: Client,
+        T: DeserializeOwned,
+    {
+        PagedIter::new(self, client, options)
     }
 
     fn single_page(&self) -> SinglePageBuilder<'_, E> {
         SinglePageBuilder::new(self)
     }
 
-    fn stream<T, C>(&'a self, client: &'a C) -> BoxStream<'_, Result<T, ApiError<C::Error>>>
+    fn stream<T, C>(&'a self, client: &'a C) -> BoxStream<'a, Result<T, ApiError<C::Error>>>
     where
         T: DeserializeOwned + Send + 'static,
         C: AsyncClient + Sync,
         E: Sync + Send,
     {
-        futures::stream::try_unfold(Some(0), move |state| async move {
-            let offset = if let Some(offset) = state {
-                offset
-            } else {
-                return Ok(None);
-            };
-            let page = SinglePageBuilder::new(self).offset(offset).build();
-            let (data, pagination) = page.query_async(client).await?;
-            if data.is_empty() {
-                Ok::<_, ApiError<C::Error>>(None)
-            } else {
-                let next_state = if data.len() < pagination.max {
-                    None
-                } else {
-                    // TODO: Dynamic page size
-                    Some(offset + pagination.max)
+        self.stream_with(client, PageOptions::default())
+    }
+
+    fn stream_with<T, C>(
+        &'a self,
+        client: &'a C,
+        options: PageOptions,
+    ) -> BoxStream<'a, Result<T, ApiError<C::Error>>>
+    where
+        T: DeserializeOwned + Send + 'static,
+        C: AsyncClient + Sync,
+        E: Sync + Send,
+    {
+        self.stream_pages_with(client, options)
+            .map_ok(|page: Page<T>| futures::stream::iter(page.items.into_iter().map(Ok)))
+            .try_flatten()
+            .boxed()
+    }
+
+    fn stream_pages<T, C>(
+        &'a self,
+        client: &'a C,
+    ) -> BoxStream<'a, Result<Page<T>, ApiError<C::Error>>>
+    where
+        T: DeserializeOwned + Send + 'static,
+        C: AsyncClient + Sync,
+        E: Sync + Send,
+    {
+        self.stream_pages_with(client, PageOptions::default())
+    }
+
+    fn stream_pages_with<T, C>(
+        &'a self,
+        client: &'a C,
+        options: PageOptions,
+    ) -> BoxStream<'a, Result<Page<T>, ApiError<C::Error>>>
+    where
+        T: DeserializeOwned + Send + 'static,
+        C: AsyncClient + Sync,
+        E: Sync + Send,
+    {
+        if options.prefetch > 0 {
+            return prefetch_pages(self, client, options);
+        }
+        let start = SinglePageBuilder::new(self)
+            .offset(0)
+            .page_size(options.page_size_for(self))
+            .build();
+        futures::stream::try_unfold(
+            Some((start, PageProgress::default())),
+            move |state| async move {
+                let (mut page, mut progress) = match state {
+                    Some(state) => state,
+                    None => return Ok::<_, ApiError<C::Error>>(None),
                 };
+                let (items, pagination): (Vec<T>, _) = page.query_async(client).await?;
+                let link = next_link(&items, &pagination);
+                progress.advance(items.len(), link.is_none());
+                let next_state = link.map(|link| {
+                    page.link = Some(link);
+                    (page, progress)
+                });
                 Ok(Some((
-                    futures::stream::iter(data.into_iter().map(Ok)),
+                    Page {
+                        items,
+                        pagination,
+                        progress,
+                    },
                     next_state,
                 )))
-            }
+            },
+        )
+        .boxed()
+    }
+}
+
+/// Stream over the pages of `paged`, keeping `options.prefetch` requests in
+/// flight ahead of the page being consumed.
+///
+/// Pages are requested by offset, so the stream ends at the first page that
+/// is shorter than the page size.
+fn prefetch_pages<'a, E, T, C>(
+    paged: &'a E,
+    client: &'a C,
+    options: PageOptions,
+) -> BoxStream<'a, Result<Page<T>, ApiError<C::Error>>>
+where
+    E: Endpoint + Pageable + Sync + Send,
+    T: DeserializeOwned + Send + 'static,
+    C: AsyncClient + Sync,
+{
+    let requested = options.page_size_for(paged);
+    let page_size = requested.unwrap_or(DEFAULT_PAGE_SIZE);
+    futures::stream::iter(0..)
+        .map(move |index: usize| async move {
+            SinglePageBuilder::new(paged)
+                .offset(index * page_size)
+                .page_size(requested)
+                .build()
+                .query_async(client)
+                .await
         })
-        .try_flatten()
+        .buffered(options.prefetch + 1)
+        .scan(
+            Some(PageProgress::default()),
+            move |state, result: Result<(Vec<T>, Pagination), _>| {
+                let page = state.take().map(|mut progress| {
+                    result.map(|(items, pagination)| {
+                        let last_page = items.len() < page_size;
+                        progress.advance(items.len(), last_page);
+                        if !last_page {
+                            *state = Some(progress);
+                        }
+                        Page {
+                            items,
+                            pagination,
+                            progress,
+                        }
+                    })
+                });
+                futures::future::ready(page)
+            },
+        )
         .boxed()
+}
+
+/// The URL of the page following a page containing `items`, or `None` if this
+/// is the last page.
+fn next_link<T>(items: &[T], pagination: &Pagination) -> Option<url::Url> {
+    if items.is_empty() {
+        return None;
     }
+    pagination.next().map(|link| link.uri.clone())
 }
 
 impl<'a, E, T, C> Query<(Vec<T>, Pagination), C> for SinglePage<'a, E>
@@ -269,31 +570,144 @@ where
     C: Client,
 {
     fn query(&self, client: &C) -> Result<(Vec<T>, Pagination), ApiError<C::Error>> {
-        let url = self.page_url(client)?;
+        self.prepare(client)?.send(client)?.page()
+    }
+}
 
-        let body = self.inner.body()?;
+#[cfg(test)]
+mod tests {
+    use http::Method;
+    use serde_json::{json, Value};
+
+    use super::*;
+    use crate::{api::games::Games, mock::MockClient};
+
+    const BASE: &str = "https://www.speedrun.com/api/v1/";
+
+    fn page(items: &[i64], next: Option<&str>) -> Value {
+        let links: Vec<_> = next
+            .map(|next| json!({ "rel": "next", "uri": format!("{}{}", BASE, next) }))
+            .into_iter()
+            .collect();
+        json!({
+            "data": items,
+            "pagination": {
+                "offset": 0,
+                "max": 2,
+                "size": items.len(),
+                "links": links,
+            },
+        })
+    }
 
-        let req = Request::builder()
-            .method(self.inner.method())
-            .uri(query::url_to_http_uri(url));
+    /// Three pages, where the `next` links use a cursor instead of offsets.
+    fn client() -> MockClient {
+        let client = MockClient::new();
+        let ok = http::StatusCode::OK;
+        client.add_json(
+            Method::GET,
+            "games?offset=0&max=2",
+            ok,
+            page(&[1, 2], Some("games?cursor=b&max=2")),
+        );
+        client.add_json(
+            Method::GET,
+            "games?cursor=b&max=2",
+            ok,
+            page(&[3, 4], Some("games?cursor=c&max=2")),
+        );
+        client.add_json(Method::GET, "games?cursor=c&max=2", ok, page(&[5], None));
+        client
+    }
 
-        let (req, data) = if let Some((mime, data)) = body.as_ref() {
-            let req = req.header(header::CONTENT_TYPE, *mime);
-            (req, data.clone())
-        } else {
-            (req, Vec::new())
-        };
+    fn games() -> Games<'static> {
+        Games::builder().build().unwrap()
+    }
 
-        let rsp = client.rest(req, data)?;
-        let status = rsp.status();
+    fn options() -> PageOptions {
+        PageOptions::new().page_size(2)
+    }
 
-        let value = serde_json::from_slice(rsp.body())?;
-        if !status.is_success() {
-            return Err(ApiError::from_speedrun_api(value));
-        }
+    #[test]
+    fn iter_follows_next_links() {
+        let client = client();
+        let games = games();
+        let mut iter = games.iter_with(&client, options());
+        let items = iter.by_ref().collect::<Result<Vec<i64>, _>>().unwrap();
+        assert_eq!(items, [1, 2, 3, 4, 5]);
+        assert_eq!(
+            iter.progress(),
+            PageProgress {
+                pages: 3,
+                items: 5,
+                finished: true,
+            }
+        );
+        assert_eq!(client.requests().len(), 3);
+    }
 
-        serde_json::from_value::<Root<Vec<T>>>(value)
-            .map(|value| (value.data, value.pagination.unwrap_or_default()))
-            .map_err(ApiError::data_type::<Vec<T>>)
+    #[tokio::test]
+    async fn stream_follows_next_links() {
+        let client = client();
+        let games = games();
+        let items: Vec<i64> = games
+            .stream_with::<i64, _>(&client, options())
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(items, [1, 2, 3, 4, 5]);
+    }
+
+    #[tokio::test]
+    async fn prefetch_requests_pages_by_offset() {
+        let client = MockClient::new();
+        let ok = http::StatusCode::OK;
+        client.add_json(Method::GET, "games?offset=0&max=2", ok, page(&[1, 2], None));
+        client.add_json(Method::GET, "games?offset=2&max=2", ok, page(&[3, 4], None));
+        client.add_json(Method::GET, "games?offset=4&max=2", ok, page(&[5], None));
+        client.add_json(Method::GET, "games?offset=6&max=2", ok, page(&[], None));
+        let games = games();
+        let items: Vec<i64> = games
+            .stream_with::<i64, _>(&client, options().prefetch(1))
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(items, [1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn empty_page_ends_iteration() {
+        let client = MockClient::new();
+        client.add_json(
+            Method::GET,
+            "games?offset=0&max=2",
+            http::StatusCode::OK,
+            page(&[], Some("games?offset=2&max=2")),
+        );
+        let games = games();
+        let items = games
+            .iter_with(&client, options())
+            .collect::<Result<Vec<i64>, _>>()
+            .unwrap();
+        assert!(items.is_empty());
+        assert_eq!(client.requests().len(), 1);
+    }
+
+    #[test]
+    fn invalid_page_size_is_rejected() {
+        let client = MockClient::new();
+        let games = games();
+        for size in [0, MAX_PAGE_SIZE + 1].iter() {
+            let result: Result<(Vec<i64>, _), _> =
+                games.single_page().page_size(*size).build().query(&client);
+            assert!(matches!(
+                result,
+                Err(ApiError::InvalidPageSize {
+                    max: MAX_PAGE_SIZE,
+                    ..
+                })
+            ));
+        }
+        assert!(client.requests().is_empty());
     }
 }
@@ -9,10 +9,11 @@ use serde::{Deserialize, Serialize};
 use super::{endpoint::Endpoint, Direction, Pageable};
 
 /// Sorting options for platforms
-#[derive(Debug, Serialize, Clone, Copy)]
+#[derive(Debug, Serialize, Clone, Copy, Default)]
 #[serde(rename_all = "kebab-case")]
 pub enum PlatformsSorting {
     /// Sorts alphanumerically by the platform name (default)
+    #[default]
     Name,
     /// Sorts by the year the platform was released
     Released,
@@ -81,12 +82,6 @@ impl<'a> Platform<'a> {
     }
 }
 
-impl Default for PlatformsSorting {
-    fn default() -> Self {
-        Self::Name
-    }
-}
-
 impl Endpoint for Platforms {
     fn method(&self) -> http::Method {
         Method::GET
@@ -9,10 +9,11 @@ use serde::{Deserialize, Serialize};
 use super::{endpoint::Endpoint, Direction, Pageable};
 
 /// Sorting options for publisher
-#[derive(Debug, Serialize, Clone, Copy)]
+#[derive(Debug, Serialize, Clone, Copy, Default)]
 #[serde(rename_all = "kebab-case")]
 pub enum PublishersSorting {
     /// Sort alphanumerically by publisher name (default)
+    #[default]
     Name,
 }
 
@@ -78,12 +79,6 @@ impl<'a> Publisher<'a> {
     }
 }
 
-impl Default for PublishersSorting {
-    fn default() -> Self {
-        Self::Name
-    }
-}
-
 impl Endpoint for Publishers {
     fn method(&self) -> http::Method {
         Method::GET
@@ -1,4 +1,9 @@
 use async_trait::async_trait;
+use http::{HeaderMap, StatusCode};
+use serde_json::Value;
+use url::Url;
+
+use crate::types::Root;
 
 use super::{ApiError, AsyncClient, Client};
 
@@ -26,3 +31,41 @@ where
     /// Perform an asynchronous query against the client.
     async fn query_async(&self, client: &C) -> Result<T, ApiError<C::Error>>;
 }
+
+/// A response with its data left untyped, returned by [`RawQuery`].
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct RawResponse {
+    /// The URL the request was sent to.
+    pub url: Url,
+    /// The HTTP status of the response.
+    pub status: StatusCode,
+    /// The headers of the response.
+    pub headers: HeaderMap,
+    /// The body of the response. `data` is `null` for empty responses.
+    pub root: Root<Value>,
+}
+
+/// Query returning the raw response, for response headers or fields that are
+/// not modelled by the types in this crate.
+///
+/// Unsuccessful responses are still returned as an [`ApiError`].
+pub trait RawQuery<C>
+where
+    C: Client,
+{
+    /// Perform a query against the client.
+    fn query_raw(&self, client: &C) -> Result<RawResponse, ApiError<C::Error>>;
+}
+
+/// Asynchronous query returning the raw response.
+///
+/// Unsuccessful responses are still returned as an [`ApiError`].
+#[async_trait]
+pub trait AsyncRawQuery<C>
+where
+    C: AsyncClient,
+{
+    /// Perform an asynchronous query against the client.
+    async fn query_raw_async(&self, client: &C) -> Result<RawResponse, ApiError<C::Error>>;
+}
@@ -4,28 +4,32 @@
 use std::{
     borrow::Cow,
     collections::{BTreeSet, HashMap},
+    error::Error,
     fmt::Display,
 };
 
 use http::Method;
 use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::types::{self, Date, RunTime, TimingMethod};
 
 use super::{
-    categories::CategoryId,
+    categories::{CategoryEmbeds, CategoryId},
     endpoint::Endpoint,
-    games::GameId,
-    levels::LevelId,
+    games::{GameEmbeds, GameId, GameVariables},
+    levels::{LevelEmbeds, LevelId},
     platforms::PlatformId,
     regions::RegionId,
     users::UserId,
     variables::{ValueId, VariableId},
-    Direction, Pageable,
+    ignore, ApiError, AsyncClient, AsyncQuery, Client, Direction, Pageable, Query,
 };
 
 /// Embeds available for runs.
 ///
-/// ## NOTE
-/// Embeds can be nested. That is not handled by this API.
+/// Embeds can be nested, e.g. [`RunEmbeds::GameWith`] with
+/// [`GameEmbeds::Platforms`] embeds `game.platforms`.
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub enum RunEmbeds {
     /// Embeds the full game resource.
@@ -41,6 +45,13 @@ pub enum RunEmbeds {
     Region,
     /// Embeds the full platform resource. Can be empty if no platform was set.
     Platform,
+    /// Embeds the full game resource, with a nested embed (`game.<embed>`).
+    GameWith(GameEmbeds),
+    /// Embeds the category resource, with a nested embed
+    /// (`category.<embed>`).
+    CategoryWith(CategoryEmbeds),
+    /// Embeds the level resource, with a nested embed (`level.<embed>`).
+    LevelWith(LevelEmbeds),
 }
 
 /// Verification status for the run.
@@ -56,10 +67,11 @@ pub enum RunStatus {
 }
 
 /// Sorting options for runs
-#[derive(Debug, Serialize, Clone, Copy)]
+#[derive(Debug, Serialize, Clone, Copy, Default)]
 #[serde(rename_all = "kebab-case")]
 pub enum RunsSorting {
     /// Sorts by the game the run was done in (default)
+    #[default]
     Game,
     /// Sorts by the run category
     Category,
@@ -109,10 +121,28 @@ pub enum SplitsIo {
     Url(url::Url),
 }
 
-// Does this belong here?
+impl From<String> for SplitsIo {
+    fn from(value: String) -> Self {
+        Self::Id(value)
+    }
+}
+
+impl From<&str> for SplitsIo {
+    fn from(value: &str) -> Self {
+        Self::Id(value.into())
+    }
+}
+
+impl From<url::Url> for SplitsIo {
+    fn from(value: url::Url) -> Self {
+        Self::Url(value)
+    }
+}
+
 /// Type of the variable value.
 #[derive(Debug, Serialize, Clone)]
 #[serde(rename_all = "kebab-case")]
+#[serde(tag = "type")]
 pub enum ValueType<'a> {
     /// Pre-defined variable
     PreDefined {
@@ -210,12 +240,19 @@ pub struct Runs<'a> {
 #[serde(rename_all = "kebab-case")]
 pub struct Run<'a> {
     #[doc = r"`ID` of the run."]
+    #[serde(skip)]
     id: RunId<'a>,
+    #[builder(setter(name = "_embed"), private, default)]
+    #[serde(serialize_with = "super::utils::serialize_as_csv")]
+    #[serde(skip_serializing_if = "BTreeSet::is_empty")]
+    embed: BTreeSet<RunEmbeds>,
 }
 
 /// Submit a new run.
 ///
-/// This endpoint requires a valid API key.
+/// This endpoint requires a valid API key. The created run is returned, and
+/// can be deserialized as a [`types::Run`] (see
+/// [`CreateRun::submit`]).
 #[derive(Debug, Builder, Serialize, Clone)]
 #[builder(setter(into, strip_option), build_fn(validate = "Self::validate"))]
 #[serde(rename_all = "kebab-case")]
@@ -224,18 +261,23 @@ pub struct CreateRun<'a> {
     category: CategoryId<'a>,
     #[doc = r"Level ID for individual level runs."]
     #[builder(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
     level: Option<LevelId<'a>>,
     #[doc = r"Optional date the run was performed (defaults to the current date)."]
     #[builder(default)]
-    date: Option<Cow<'a, str>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    date: Option<Date>,
     #[doc = r"Optional region for the run. Some games require a region to be submitted."]
     #[builder(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
     region: Option<RegionId<'a>>,
     #[doc = r"Optional platform for the run. Some games require a platform to be submitted."]
     #[builder(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
     platform: Option<PlatformId<'a>>,
     #[doc = r"If the run has been verified by a moderator. Can only be set if the submitting user is a moderator of the game."]
     #[builder(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
     verified: Option<bool>,
     #[builder(setter(name = "_times"), private, default)]
     times: Times,
@@ -243,28 +285,112 @@ pub struct CreateRun<'a> {
     #[serde(skip_serializing_if = "Vec::is_empty")]
     players: Vec<Player<'a>>,
     #[doc = r"When `true` the run was performed on an emulator (default: false)."]
+    #[builder(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
     emulated: Option<bool>,
     #[doc = r"A valid video URL. Optional, but some games require a video to be included."]
     #[builder(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
     video: Option<url::Url>,
     #[doc = r"Optional comment on the run. Can include additional video URLs."]
     #[builder(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
     comment: Option<String>,
     #[doc = r"Splits.io ID or URL for the splits for the run."]
     #[builder(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
     splitsio: Option<SplitsIo>,
-    #[doc = r"Variable values for the new run. Some games have mandatory variables."]
-    #[builder(default)]
+    #[builder(setter(name = "_variables"), private, default)]
     #[serde(skip_serializing_if = "HashMap::is_empty")]
     variables: HashMap<VariableId<'a>, ValueType<'a>>,
 }
 
+/// The API expects submitted runs wrapped in a `run` object.
+#[derive(Serialize)]
+struct RunBody<'r, T> {
+    run: &'r T,
+}
+
 #[derive(Default, Debug, Serialize, Clone)]
 #[serde(rename_all = "snake_case")]
 struct Times {
-    realtime: Option<f64>,
-    realtime_noloads: Option<f64>,
-    ingame: Option<f64>,
+    #[serde(
+        serialize_with = "super::utils::serialize_as_seconds",
+        skip_serializing_if = "Option::is_none"
+    )]
+    realtime: Option<RunTime>,
+    #[serde(
+        serialize_with = "super::utils::serialize_as_seconds",
+        skip_serializing_if = "Option::is_none"
+    )]
+    realtime_noloads: Option<RunTime>,
+    #[serde(
+        serialize_with = "super::utils::serialize_as_seconds",
+        skip_serializing_if = "Option::is_none"
+    )]
+    ingame: Option<RunTime>,
+}
+
+/// Edit an existing run.
+///
+/// The API has no endpoint for editing runs. Instead, the run is submitted
+/// again with the changes applied, keeping the category, players, date,
+/// system and any other values that were not changed, and the original run
+/// is deleted once the new one has been created. The new run has a new `ID`.
+///
+/// A run can only be submitted with a single video URL. The first video link
+/// of the run is kept as the video, and any other links are added to the end
+/// of the comment, unless a new video is set.
+///
+/// Requires a valid API key for an authenticated user. The authenticated user
+/// must have permission to submit runs for the players of the run and to
+/// delete the run (usually a moderator of the game). Runs that were verified
+/// are submitted as verified, which is only allowed for moderators.
+#[derive(Debug, Builder, Clone)]
+#[builder(setter(into, strip_option))]
+pub struct EditRun<'a> {
+    #[doc = r"The run to edit."]
+    run: types::Run<'a>,
+    #[doc = r"Updated date the run was performed."]
+    #[builder(default)]
+    date: Option<Date>,
+    #[builder(setter(name = "_times"), private, default)]
+    times: Times,
+    #[doc = r"Updated video URL."]
+    #[builder(default)]
+    video: Option<url::Url>,
+    #[doc = r"Updated comment on the run."]
+    #[builder(default)]
+    comment: Option<String>,
+    #[doc = r"Updated splits.io ID or URL for the splits for the run."]
+    #[builder(default)]
+    splitsio: Option<SplitsIo>,
+    #[builder(setter(name = "_variables"), private, default)]
+    variables: HashMap<VariableId<'a>, ValueType<'a>>,
+    #[builder(setter(name = "_definitions"), private, default)]
+    definitions: Option<Vec<types::Variable<'a>>>,
+}
+
+/// Errors that occur while editing a run with [`EditRun`].
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum EditError<E>
+where
+    E: Error + Send + Sync + 'static,
+{
+    /// Error loading the variables of the game or submitting the edited run.
+    /// The original run was not changed.
+    #[error(transparent)]
+    Api(#[from] ApiError<E>),
+    /// The edited run was created, but the original run could not be
+    /// deleted. Both runs now exist.
+    #[error("created run {} but failed to delete the original run: {source}", created.id)]
+    DeleteFailed {
+        /// The run that was created.
+        created: Box<types::Run<'static>>,
+        /// Error deleting the original run.
+        source: ApiError<E>,
+    },
 }
 
 /// Update the verification status for the run.
@@ -308,6 +434,10 @@ pub struct UpdateRunPlayers<'a> {
 ///
 /// Requires a valid API key for an authenticated user. Regular users can only
 /// delete their own runs. Moderators can delete runs by other users also.
+///
+/// Wrap the endpoint with [`ignore`] to only check that the run was deleted.
+///
+/// [`ignore`]: super::ignore
 #[derive(Debug, Builder, Serialize, Clone)]
 #[builder(setter(into, strip_option))]
 #[serde(rename_all = "kebab-case")]
@@ -347,22 +477,114 @@ impl<'a> Run<'a> {
     }
 }
 
+impl<'a> RunBuilder<'a> {
+    /// Add an embedded resource to this result
+    pub fn embed(&mut self, embed: RunEmbeds) -> &mut Self {
+        self.embed.get_or_insert_with(BTreeSet::new).insert(embed);
+        self
+    }
+
+    /// Add multiple embedded resources to this result
+    pub fn embeds<I>(&mut self, iter: I) -> &mut Self
+    where
+        I: Iterator<Item = RunEmbeds>,
+    {
+        self.embed.get_or_insert_with(BTreeSet::new).extend(iter);
+        self
+    }
+}
+
 impl<'a> CreateRun<'a> {
     /// Create a builder for this endpoint
-    pub fn buider() -> CreateRunBuilder<'a> {
+    pub fn builder() -> CreateRunBuilder<'a> {
         CreateRunBuilder::default()
     }
+
+    /// Create a builder for this endpoint
+    #[deprecated(note = "use `CreateRun::builder` instead")]
+    pub fn buider() -> CreateRunBuilder<'a> {
+        Self::builder()
+    }
+
+    /// Category `ID` of the run.
+    pub fn category(&self) -> &CategoryId<'a> {
+        &self.category
+    }
+
+    /// Level `ID` of the run, for individual level runs.
+    pub fn level(&self) -> Option<&LevelId<'a>> {
+        self.level.as_ref()
+    }
+
+    /// Region the run was performed in.
+    pub fn region(&self) -> Option<&RegionId<'a>> {
+        self.region.as_ref()
+    }
+
+    /// Platform the run was performed on.
+    pub fn platform(&self) -> Option<&PlatformId<'a>> {
+        self.platform.as_ref()
+    }
+
+    /// Time of the run using the given timing method.
+    pub fn time(&self, method: TimingMethod) -> Option<RunTime> {
+        match method {
+            TimingMethod::Realtime => self.times.realtime,
+            TimingMethod::RealtimeNoloads => self.times.realtime_noloads,
+            TimingMethod::Ingame => self.times.ingame,
+        }
+    }
+
+    /// Players that participated in the run.
+    pub fn players(&self) -> &[Player<'a>] {
+        &self.players
+    }
+
+    /// Whether the run was performed on an emulator.
+    pub fn emulated(&self) -> Option<bool> {
+        self.emulated
+    }
+
+    /// Video URL of the run.
+    pub fn video(&self) -> Option<&url::Url> {
+        self.video.as_ref()
+    }
+
+    /// Values of the variables of the run.
+    pub fn variables(&self) -> &HashMap<VariableId<'a>, ValueType<'a>> {
+        &self.variables
+    }
+
+    /// Submit the run using a synchronous client, returning the created run.
+    pub fn submit<C>(&self, client: &C) -> Result<types::Run<'static>, ApiError<C::Error>>
+    where
+        C: Client,
+    {
+        self.query(client)
+    }
+
+    /// Submit the run using an asynchronous client, returning the created
+    /// run.
+    pub async fn submit_async<C>(
+        &self,
+        client: &C,
+    ) -> Result<types::Run<'static>, ApiError<C::Error>>
+    where
+        C: AsyncClient + Sync,
+    {
+        self.query_async(client).await
+    }
 }
 
 impl<'a> CreateRunBuilder<'a> {
     /// Real-world time of the run
-    pub fn realtime<T: Into<f64>>(&mut self, value: T) -> &mut Self {
+    pub fn realtime<T: Into<RunTime>>(&mut self, value: T) -> &mut Self {
         self.times.get_or_insert_with(Times::default).realtime = Some(value.into());
         self
     }
 
     /// Real-world time of the run, excluding the loading times
-    pub fn realtime_noloads<T: Into<f64>>(&mut self, value: T) -> &mut Self {
+    pub fn realtime_noloads<T: Into<RunTime>>(&mut self, value: T) -> &mut Self {
         self.times
             .get_or_insert_with(Times::default)
             .realtime_noloads = Some(value.into());
@@ -370,7 +592,7 @@ impl<'a> CreateRunBuilder<'a> {
     }
 
     /// Time measured by the game
-    pub fn ingame<T: Into<f64>>(&mut self, value: T) -> &mut Self {
+    pub fn ingame<T: Into<RunTime>>(&mut self, value: T) -> &mut Self {
         self.times.get_or_insert_with(Times::default).ingame = Some(value.into());
         self
     }
@@ -390,21 +612,279 @@ impl<'a> CreateRunBuilder<'a> {
         self
     }
 
+    /// Set the value of a variable for this run.
+    pub fn variable<Var>(&mut self, variable: Var, value: ValueType<'a>) -> &mut Self
+    where
+        Var: Into<VariableId<'a>>,
+    {
+        self.variables
+            .get_or_insert_with(HashMap::new)
+            .insert(variable.into(), value);
+        self
+    }
+
+    /// Set the values of multiple variables for this run.
+    pub fn variables<I, Var>(&mut self, iter: I) -> &mut Self
+    where
+        I: IntoIterator<Item = (Var, ValueType<'a>)>,
+        Var: Into<VariableId<'a>>,
+    {
+        self.variables
+            .get_or_insert_with(HashMap::new)
+            .extend(iter.into_iter().map(|(k, v)| (k.into(), v)));
+        self
+    }
+
     fn validate(&self) -> Result<(), String> {
-        if let Some(times) = &self.times {
-            if times.realtime.is_none()
-                && times.realtime_noloads.is_none()
-                && times.ingame.is_none()
-            {
-                return Err("At least one time must be set. Set one of `realtime`, \
-                            `realtime_noloads`, or `ingame`."
-                    .into());
-            }
+        let has_time = self.times.as_ref().is_some_and(|times| {
+            times.realtime.is_some() || times.realtime_noloads.is_some() || times.ingame.is_some()
+        });
+        if !has_time {
+            return Err("At least one time must be set. Set one of `realtime`, \
+                        `realtime_noloads`, or `ingame`."
+                .into());
         }
         Ok(())
     }
 }
 
+impl<'a> EditRun<'a> {
+    /// Create a builder for this helper.
+    pub fn builder() -> EditRunBuilder<'a> {
+        EditRunBuilder::default()
+    }
+
+    /// The submission that replaces the run.
+    ///
+    /// Values of variables are submitted as pre-defined values, unless the
+    /// variables of the game were set with
+    /// [`variable_definitions`](EditRunBuilder::variable_definitions).
+    pub fn create_run(&self) -> CreateRun<'a> {
+        self.submission(self.definitions.as_deref().unwrap_or_default())
+    }
+
+    /// Apply the changes using a synchronous client, returning the new run.
+    ///
+    /// The variables of the game are loaded first if the run has values for
+    /// any variables and no variables were set with
+    /// [`variable_definitions`](EditRunBuilder::variable_definitions).
+    pub fn apply<C>(&self, client: &C) -> Result<types::Run<'static>, EditError<C::Error>>
+    where
+        C: Client,
+    {
+        let submission = match self.variables_endpoint() {
+            Some(endpoint) => {
+                let definitions: Vec<types::Variable<'static>> = endpoint.query(client)?;
+                self.submission(&definitions)
+            }
+            None => self.create_run(),
+        };
+        let created = submission.submit(client)?;
+        match ignore(self.delete_run()).query(client) {
+            Ok(()) => Ok(created),
+            Err(source) => Err(EditError::DeleteFailed {
+                created: Box::new(created),
+                source,
+            }),
+        }
+    }
+
+    /// Apply the changes using an asynchronous client, returning the new run.
+    ///
+    /// See [`apply`](Self::apply).
+    pub async fn apply_async<C>(
+        &self,
+        client: &C,
+    ) -> Result<types::Run<'static>, EditError<C::Error>>
+    where
+        C: AsyncClient + Sync,
+    {
+        let submission = match self.variables_endpoint() {
+            Some(endpoint) => {
+                let definitions: Vec<types::Variable<'static>> =
+                    endpoint.query_async(client).await?;
+                self.submission(&definitions)
+            }
+            None => self.create_run(),
+        };
+        let created = submission.submit_async(client).await?;
+        match ignore(self.delete_run()).query_async(client).await {
+            Ok(()) => Ok(created),
+            Err(source) => Err(EditError::DeleteFailed {
+                created: Box::new(created),
+                source,
+            }),
+        }
+    }
+
+    fn submission(&self, definitions: &[types::Variable<'_>]) -> CreateRun<'a> {
+        let run = &self.run;
+        let times = Times {
+            realtime: self.times.realtime.or(run.times.realtime),
+            realtime_noloads: self.times.realtime_noloads.or(run.times.realtime_noloads),
+            ingame: self.times.ingame.or(run.times.ingame),
+        };
+        let players = run
+            .players
+            .iter()
+            .map(|player| match player {
+                types::Player::User { id, .. } => Player::User { id: id.clone() },
+                types::Player::Guest { name, .. } => Player::Guest {
+                    name: name.clone().into(),
+                },
+            })
+            .collect();
+        let mut variables: HashMap<_, _> = run
+            .values
+            .iter()
+            .map(|(variable, value)| {
+                let value = ValueId::from(value.clone());
+                let user_defined = definitions
+                    .iter()
+                    .find(|definition| definition.id == *variable)
+                    .is_some_and(|definition| {
+                        definition.user_defined && !definition.values.values.contains_key(&value)
+                    });
+                let value = if user_defined {
+                    ValueType::UserDefined { value }
+                } else {
+                    ValueType::PreDefined { value }
+                };
+                (variable.clone(), value)
+            })
+            .collect();
+        variables.extend(self.variables.clone());
+        let (video, comment) = self.video_and_comment();
+
+        CreateRun {
+            category: run.category.clone(),
+            level: run.level.clone(),
+            date: self.date.or(run.date),
+            region: run.system.region.clone(),
+            platform: run.system.platform.clone(),
+            verified: matches!(run.status, types::Status::Verified { .. }).then_some(true),
+            times,
+            players,
+            emulated: Some(run.system.emulated),
+            video,
+            comment,
+            splitsio: self
+                .splitsio
+                .clone()
+                .or_else(|| run.splits.as_ref().map(|link| link.uri.clone().into())),
+            variables,
+        }
+    }
+
+    /// The video of the new run, and the comment with the video links that
+    /// did not fit in the video field.
+    fn video_and_comment(&self) -> (Option<url::Url>, Option<String>) {
+        let comment = self.comment.clone().or_else(|| self.run.comment.clone());
+        if self.video.is_some() {
+            return (self.video.clone(), comment);
+        }
+        let links = self
+            .run
+            .videos
+            .as_ref()
+            .map(|videos| videos.links.as_slice())
+            .unwrap_or_default();
+        let video = links.iter().find_map(|link| link.uri.url()).cloned();
+        let extra: Vec<_> = links
+            .iter()
+            .map(|link| link.uri.as_str())
+            .filter(|uri| video.as_ref().is_none_or(|video| video.as_str() != *uri))
+            .filter(|uri| {
+                comment
+                    .as_ref()
+                    .is_none_or(|comment| !comment.contains(uri))
+            })
+            .collect();
+        if extra.is_empty() {
+            return (video, comment);
+        }
+        let comment = match comment {
+            Some(comment) if !comment.is_empty() => format!("{}\n{}", comment, extra.join("\n")),
+            _ => extra.join("\n"),
+        };
+        (video, Some(comment))
+    }
+
+    fn variables_endpoint(&self) -> Option<GameVariables<'a>> {
+        if self.run.values.is_empty() || self.definitions.is_some() {
+            return None;
+        }
+        Some(
+            GameVariables::builder()
+                .id(self.run.game.clone())
+                .build()
+                .expect("all required fields are set"),
+        )
+    }
+
+    fn delete_run(&self) -> DeleteRun<'a> {
+        DeleteRun::builder()
+            .id(self.run.id.clone())
+            .build()
+            .expect("all required fields are set")
+    }
+}
+
+impl<'a> EditRunBuilder<'a> {
+    /// The variables of the game of the run, used to resubmit the values of
+    /// user-defined variables as user-defined values.
+    pub fn variable_definitions<I>(&mut self, iter: I) -> &mut Self
+    where
+        I: IntoIterator<Item = types::Variable<'a>>,
+    {
+        self.definitions = Some(Some(iter.into_iter().collect()));
+        self
+    }
+
+    /// Updated real-world time of the run
+    pub fn realtime<T: Into<RunTime>>(&mut self, value: T) -> &mut Self {
+        self.times.get_or_insert_with(Times::default).realtime = Some(value.into());
+        self
+    }
+
+    /// Updated real-world time of the run, excluding the loading times
+    pub fn realtime_noloads<T: Into<RunTime>>(&mut self, value: T) -> &mut Self {
+        self.times
+            .get_or_insert_with(Times::default)
+            .realtime_noloads = Some(value.into());
+        self
+    }
+
+    /// Updated time measured by the game
+    pub fn ingame<T: Into<RunTime>>(&mut self, value: T) -> &mut Self {
+        self.times.get_or_insert_with(Times::default).ingame = Some(value.into());
+        self
+    }
+
+    /// Change the value of a variable for this run.
+    pub fn variable<Var>(&mut self, variable: Var, value: ValueType<'a>) -> &mut Self
+    where
+        Var: Into<VariableId<'a>>,
+    {
+        self.variables
+            .get_or_insert_with(HashMap::new)
+            .insert(variable.into(), value);
+        self
+    }
+
+    /// Change the values of multiple variables for this run.
+    pub fn variables<I, Var>(&mut self, iter: I) -> &mut Self
+    where
+        I: IntoIterator<Item = (Var, ValueType<'a>)>,
+        Var: Into<VariableId<'a>>,
+    {
+        self.variables
+            .get_or_insert_with(HashMap::new)
+            .extend(iter.into_iter().map(|(k, v)| (k.into(), v)));
+        self
+    }
+}
+
 impl<'a> UpdateRunStatus<'a> {
     /// Create a builder for this endpoint
     pub fn builder() -> UpdateRunStatusBuilder<'a> {
@@ -444,24 +924,21 @@ impl<'a> DeleteRun<'a> {
 }
 
 impl RunEmbeds {
-    fn as_str(&self) -> &'static str {
+    fn path(&self) -> Cow<'static, str> {
         match self {
-            RunEmbeds::Game => "game",
-            RunEmbeds::Category => "category",
-            RunEmbeds::Level => "level",
-            RunEmbeds::Players => "players",
-            RunEmbeds::Region => "region",
-            RunEmbeds::Platform => "platform",
+            RunEmbeds::Game => "game".into(),
+            RunEmbeds::Category => "category".into(),
+            RunEmbeds::Level => "level".into(),
+            RunEmbeds::Players => "players".into(),
+            RunEmbeds::Region => "region".into(),
+            RunEmbeds::Platform => "platform".into(),
+            RunEmbeds::GameWith(embed) => format!("game.{}", embed.path()).into(),
+            RunEmbeds::CategoryWith(embed) => format!("category.{}", embed.path()).into(),
+            RunEmbeds::LevelWith(embed) => format!("level.{}", embed.path()).into(),
         }
     }
 }
 
-impl Default for RunsSorting {
-    fn default() -> Self {
-        Self::Game
-    }
-}
-
 impl Endpoint for Runs<'_> {
     fn method(&self) -> http::Method {
         Method::GET
@@ -484,6 +961,10 @@ impl Endpoint for Run<'_> {
     fn endpoint(&self) -> Cow<'static, str> {
         format!("/runs/{}", self.id).into()
     }
+
+    fn query_parameters(&self) -> Result<Cow<'static, str>, super::error::BodyError> {
+        Ok(serde_urlencoded::to_string(self)?.into())
+    }
 }
 
 impl Endpoint for CreateRun<'_> {
@@ -496,7 +977,8 @@ impl Endpoint for CreateRun<'_> {
     }
 
     fn body(&self) -> Result<Option<(&'static str, Vec<u8>)>, super::error::BodyError> {
-        Ok(serde_json::to_vec(self).map(|body| Some(("application/json", body)))?)
+        let body = RunBody { run: self };
+        Ok(serde_json::to_vec(&body).map(|body| Some(("application/json", body)))?)
     }
 
     fn requires_authentication(&self) -> bool {
@@ -554,10 +1036,146 @@ impl Endpoint for DeleteRun<'_> {
     }
 }
 
-impl From<&RunEmbeds> for &'static str {
+impl From<&RunEmbeds> for Cow<'static, str> {
     fn from(value: &RunEmbeds) -> Self {
-        value.as_str()
+        value.path()
     }
 }
 
 impl Pageable for Runs<'_> {}
+
+#[cfg(test)]
+mod tests {
+    use http::{Method, StatusCode};
+    use serde_json::json;
+
+    use super::{EditError, EditRun};
+    use crate::{
+        mock::MockClient,
+        test_utils::{run_json, run_with, variable_json},
+        types::Variable,
+    };
+
+    fn edited_run() -> crate::types::Run<'static> {
+        run_with(
+            "old",
+            Some(60.0),
+            Some("2020-01-01"),
+            json!({
+                "comment": "gg",
+                "videos": {
+                    "text": null,
+                    "links": [
+                        { "uri": "https://youtu.be/abc" },
+                        { "uri": "https://www.twitch.tv/videos/1" },
+                        { "uri": "not a link" },
+                    ],
+                },
+                "values": { "platform": "a", "seed": "12345" },
+            }),
+        )
+    }
+
+    fn definitions() -> Vec<serde_json::Value> {
+        let mut seed = variable_json("seed", false, false, json!({ "type": "global" }));
+        seed["user-defined"] = json!(true);
+        vec![
+            variable_json("platform", false, false, json!({ "type": "global" })),
+            seed,
+        ]
+    }
+
+    #[test]
+    fn create_run_keeps_values_and_videos() {
+        let definitions: Vec<Variable<'static>> = definitions()
+            .into_iter()
+            .map(|variable| serde_json::from_value(variable).unwrap())
+            .collect();
+        let edit = EditRun::builder()
+            .run(edited_run())
+            .realtime(crate::types::RunTime::from_millis(59_000))
+            .variable_definitions(definitions)
+            .build()
+            .unwrap();
+        let body = serde_json::to_value(edit.create_run()).unwrap();
+
+        assert_eq!(body["times"], json!({ "realtime": 59.0 }));
+        assert_eq!(body["date"], "2020-01-01");
+        assert_eq!(body["video"], "https://youtu.be/abc");
+        assert_eq!(
+            body["comment"],
+            "gg\nhttps://www.twitch.tv/videos/1\nnot a link"
+        );
+        assert_eq!(
+            body["variables"]["platform"],
+            json!({ "type": "pre-defined", "value": "a" })
+        );
+        assert_eq!(
+            body["variables"]["seed"],
+            json!({ "type": "user-defined", "value": "12345" })
+        );
+    }
+
+    #[test]
+    fn new_video_replaces_links() {
+        let edit = EditRun::builder()
+            .run(edited_run())
+            .video(url::Url::parse("https://youtu.be/new").unwrap())
+            .build()
+            .unwrap();
+        let body = serde_json::to_value(edit.create_run()).unwrap();
+        assert_eq!(body["video"], "https://youtu.be/new");
+        assert_eq!(body["comment"], "gg");
+    }
+
+    #[test]
+    fn apply_returns_created_run_when_delete_fails() {
+        let client = MockClient::new().authenticated(true);
+        client.add_data(Method::GET, "games/o1y9wo6q/variables", definitions());
+        client.add_data(
+            Method::POST,
+            "runs",
+            run_json("new", Some(60.0), None, json!({})),
+        );
+        client.add_json(
+            Method::DELETE,
+            "runs/old",
+            StatusCode::FORBIDDEN,
+            json!({ "status": 403, "message": "not allowed" }),
+        );
+
+        let edit = EditRun::builder().run(edited_run()).build().unwrap();
+        match edit.apply(&client) {
+            Err(EditError::DeleteFailed { created, .. }) => assert_eq!(created.id, "new".into()),
+            other => panic!("unexpected result: {:?}", other),
+        }
+
+        let requests = client.requests();
+        assert_eq!(requests.len(), 3);
+        let body: serde_json::Value = serde_json::from_slice(&requests[1].body).unwrap();
+        assert_eq!(body["run"]["variables"]["seed"]["type"], "user-defined");
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn apply_async_deletes_the_original() {
+        let client = MockClient::new().authenticated(true);
+        client.add_data(
+            Method::POST,
+            "runs",
+            run_json("new", Some(60.0), None, json!({})),
+        );
+        client.add_json(
+            Method::DELETE,
+            "runs/old",
+            StatusCode::NO_CONTENT,
+            json!(null),
+        );
+
+        let run = run_with("old", Some(60.0), None, json!({}));
+        let edit = EditRun::builder().run(run).build().unwrap();
+        let created = edit.apply_async(&client).await.unwrap();
+        assert_eq!(created.id, "new".into());
+        assert_eq!(client.requests().len(), 2);
+    }
+}
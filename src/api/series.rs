@@ -29,11 +29,12 @@ pub enum SeriesEmbeds {
 }
 
 /// Sorting options for game series
-#[derive(Debug, Serialize, Clone, Copy)]
+#[derive(Debug, Serialize, Clone, Copy, Default)]
 #[serde(rename_all = "kebab-case")]
 pub enum SeriesSorting {
     /// Sorts alphanumerically by the international name (default)
     #[serde(rename = "name.int")]
+    #[default]
     NameInternational,
     /// Sorts alphanumerically by the Japanese name
     #[serde(rename = "name.jap")]
@@ -317,12 +318,6 @@ impl SeriesEmbeds {
     }
 }
 
-impl Default for SeriesSorting {
-    fn default() -> Self {
-        Self::NameInternational
-    }
-}
-
 impl Endpoint for ListSeries<'_> {
     fn method(&self) -> http::Method {
         Method::GET
@@ -361,9 +356,9 @@ impl Endpoint for SeriesGames<'_> {
     }
 }
 
-impl From<&SeriesEmbeds> for &'static str {
+impl From<&SeriesEmbeds> for Cow<'static, str> {
     fn from(value: &SeriesEmbeds) -> Self {
-        value.as_str()
+        value.as_str().into()
     }
 }
 
@@ -9,11 +9,12 @@ use serde::{Deserialize, Serialize};
 use super::{endpoint::Endpoint, games::GameId, runs::RunEmbeds, Direction, Pageable};
 
 /// Sorting options for users
-#[derive(Debug, Serialize, Clone, Copy)]
+#[derive(Debug, Serialize, Clone, Copy, Default)]
 #[serde(rename_all = "kebab-case")]
 pub enum UsersSorting {
     /// Sorts alphanumerically by the international name (default)
     #[serde(rename = "name.int")]
+    #[default]
     NameInternational,
     /// Sorts alphanumerically by the Japanese name
     #[serde(rename = "name.jap")]
@@ -145,12 +146,6 @@ impl<'a> UserPersonalBestsBuilder<'a> {
     }
 }
 
-impl Default for UsersSorting {
-    fn default() -> Self {
-        Self::NameInternational
-    }
-}
-
 impl Endpoint for Users<'_> {
     fn method(&self) -> http::Method {
         Method::GET
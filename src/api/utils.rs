@@ -1,13 +1,40 @@
+use std::borrow::Cow;
+
 use serde::Serializer;
 
+use crate::types::RunTime;
+
 pub(crate) fn serialize_as_csv<S, T>(
     iter: impl IntoIterator<Item = T>,
     serializer: S,
 ) -> Result<S::Ok, S::Error>
 where
-    T: Into<&'static str>,
+    T: Into<Cow<'static, str>>,
     S: Serializer,
 {
     let out: Vec<_> = iter.into_iter().map(Into::into).collect();
     serializer.serialize_str(&out.join(","))
 }
+
+/// Serialize an optional run time as a number of seconds, as expected by the
+/// API when submitting runs.
+pub(crate) fn serialize_as_seconds<S>(
+    value: &Option<RunTime>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    match value {
+        Some(time) => serializer.serialize_some(&time.as_secs_f64()),
+        None => serializer.serialize_none(),
+    }
+}
+
+/// 64-bit FNV-1a hash. Unlike the hashers in `std`, the output is stable across
+/// releases, so it can be used for names of files written to disk.
+pub(crate) fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
@@ -0,0 +1,632 @@
+//! Caching of API responses.
+//!
+//! [`CachedClient`] wraps any [`Client`] or [`AsyncClient`] and stores
+//! successful `GET` responses, keyed on the full request URL (including the
+//! query parameters set by the endpoint). Cached responses are returned until
+//! their TTL runs out. After that, responses carrying an `ETag` or
+//! `Last-Modified` validator are revalidated with a conditional request.
+//!
+//! Responses for endpoints that require authentication (e.g. [`Profile`] and
+//! [`Notifications`]) are never cached. This includes every page of paginated
+//! endpoints that require authentication.
+//!
+//! Cache hits are answered without calling the inner client, so they skip
+//! everything the inner client does when sending a request: rate limiting,
+//! retries and recording fixtures. The [`RestClient::intercept_request`] and
+//! [`RestClient::intercept_response`] hooks of the inner client are forwarded,
+//! so they still see every query, including cache hits.
+//!
+//! # Example
+//!
+//! ```rust ,no_run
+//! use std::time::Duration;
+//!
+//! use speedrun_api::{
+//!     api::{games::Game, Query},
+//!     cache::CachedClient,
+//!     types, SpeedrunApiBuilder,
+//! };
+//!
+//! # fn main() -> speedrun_api::error::SpeedrunApiResult<()> {
+//! let client = SpeedrunApiBuilder::new().build()?;
+//! let client = CachedClient::new(client)
+//!     .default_ttl(Duration::from_secs(60))
+//!     .ttl("leaderboards", Duration::from_secs(5 * 60))
+//!     .ttl("games", Duration::from_secs(60 * 60));
+//!
+//! let endpoint = Game::builder().id("v1pxjz68").build().unwrap();
+//! // The second query is answered from the cache.
+//! let game: types::Game = endpoint.query(&client)?;
+//! let game: types::Game = endpoint.query(&client)?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`Profile`]: crate::api::profile::Profile
+//! [`Notifications`]: crate::api::notifications::Notifications
+use std::{
+    collections::HashMap,
+    fs, io,
+    path::PathBuf,
+    sync::Mutex,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use http::{
+    header, request::Builder as RequestBuilder, HeaderMap, HeaderValue, Method, Request, Response,
+    StatusCode,
+};
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::{
+    api::{self, ApiError, AsyncClient, Client, RestClient},
+    middleware::RequestInfo,
+};
+
+const DEFAULT_TTL: Duration = Duration::from_secs(60);
+
+/// A response stored in a [`CacheStore`].
+#[derive(Debug, Clone)]
+pub struct CachedResponse {
+    /// HTTP status of the response.
+    pub status: StatusCode,
+    /// Headers of the response.
+    pub headers: HeaderMap,
+    /// Body of the response.
+    pub body: Bytes,
+    /// Point in time after which the response has to be revalidated.
+    pub expires: SystemTime,
+}
+
+/// Storage backend for cached responses.
+pub trait CacheStore: Send + Sync {
+    /// Get the response stored for `key`.
+    fn get(&self, key: &str) -> Option<CachedResponse>;
+
+    /// Store `response` for `key`, replacing any previous response.
+    fn insert(&self, key: &str, response: CachedResponse);
+
+    /// Remove the response stored for `key`.
+    fn remove(&self, key: &str);
+}
+
+/// Stores cached responses in memory.
+#[derive(Debug, Default)]
+pub struct MemoryStore {
+    entries: Mutex<HashMap<String, CachedResponse>>,
+}
+
+/// Stores cached responses as files in a directory.
+#[derive(Debug, Clone)]
+pub struct DiskStore {
+    dir: PathBuf,
+}
+
+/// A client wrapper caching responses of the inner client.
+#[derive(Debug)]
+pub struct CachedClient<C, S = MemoryStore> {
+    inner: C,
+    store: S,
+    default_ttl: Duration,
+    ttls: Vec<(String, Duration)>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct DiskEntry {
+    key: String,
+    status: u16,
+    headers: Vec<(String, String)>,
+    expires: u64,
+}
+
+impl CachedResponse {
+    fn is_fresh(&self) -> bool {
+        self.expires > SystemTime::now()
+    }
+
+    fn to_response(&self) -> Result<Response<Bytes>, http::Error> {
+        let mut rsp = Response::builder().status(self.status);
+        if let Some(headers) = rsp.headers_mut() {
+            headers.extend(self.headers.clone());
+        }
+        rsp.body(self.body.clone())
+    }
+}
+
+impl MemoryStore {
+    /// Create a new, empty, in-memory store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Remove all stored responses.
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, CachedResponse>> {
+        self.entries.lock().unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+impl CacheStore for MemoryStore {
+    fn get(&self, key: &str) -> Option<CachedResponse> {
+        self.lock().get(key).cloned()
+    }
+
+    fn insert(&self, key: &str, response: CachedResponse) {
+        self.lock().insert(key.into(), response);
+    }
+
+    fn remove(&self, key: &str) {
+        self.lock().remove(key);
+    }
+}
+
+impl DiskStore {
+    /// Create a new store keeping its files in `dir`. The directory is created
+    /// if it does not exist.
+    pub fn new<P>(dir: P) -> io::Result<Self>
+    where
+        P: Into<PathBuf>,
+    {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)?;
+        Ok(Self { dir })
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.dir
+            .join(format!("{:016x}.cache", api::fnv1a(key.as_bytes())))
+    }
+
+    fn read(&self, key: &str) -> io::Result<Option<CachedResponse>> {
+        let data = match fs::read(self.path(key)) {
+            Ok(data) => data,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err),
+        };
+        let split = data.iter().position(|b| *b == b'\n').unwrap_or(data.len());
+        let entry: DiskEntry = serde_json::from_slice(&data[..split])?;
+        // Guard against hash collisions.
+        if entry.key != key {
+            return Ok(None);
+        }
+        let status = StatusCode::from_u16(entry.status)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        let mut headers = HeaderMap::new();
+        for (name, value) in entry.headers {
+            if let (Ok(name), Ok(value)) = (
+                header::HeaderName::from_bytes(name.as_bytes()),
+                HeaderValue::from_str(&value),
+            ) {
+                headers.append(name, value);
+            }
+        }
+        Ok(Some(CachedResponse {
+            status,
+            headers,
+            body: Bytes::copy_from_slice(data.get(split + 1..).unwrap_or_default()),
+            expires: UNIX_EPOCH + Duration::from_secs(entry.expires),
+        }))
+    }
+
+    fn write(&self, key: &str, response: &CachedResponse) -> io::Result<()> {
+        let entry = DiskEntry {
+            key: key.into(),
+            status: response.status.as_u16(),
+            headers: response
+                .headers
+                .iter()
+                .filter_map(|(name, value)| {
+                    Some((name.as_str().into(), value.to_str().ok()?.into()))
+                })
+                .collect(),
+            expires: response
+                .expires
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+        };
+        let mut data = serde_json::to_vec(&entry)?;
+        data.push(b'\n');
+        data.extend_from_slice(&response.body);
+
+        let path = self.path(key);
+        let tmp = path.with_extension("tmp");
+        fs::write(&tmp, data)?;
+        fs::rename(tmp, path)
+    }
+}
+
+impl CacheStore for DiskStore {
+    fn get(&self, key: &str) -> Option<CachedResponse> {
+        self.read(key).unwrap_or_else(|err| {
+            warn!("failed reading cache entry for {}: {}", key, err);
+            None
+        })
+    }
+
+    fn insert(&self, key: &str, response: CachedResponse) {
+        if let Err(err) = self.write(key, &response) {
+            warn!("failed writing cache entry for {}: {}", key, err);
+        }
+    }
+
+    fn remove(&self, key: &str) {
+        if let Err(err) = fs::remove_file(self.path(key)) {
+            if err.kind() != io::ErrorKind::NotFound {
+                warn!("failed removing cache entry for {}: {}", key, err);
+            }
+        }
+    }
+}
+
+impl<C> CachedClient<C, MemoryStore> {
+    /// Wrap `inner`, caching responses in memory.
+    pub fn new(inner: C) -> Self {
+        Self::with_store(inner, MemoryStore::new())
+    }
+}
+
+impl<C, S> CachedClient<C, S>
+where
+    S: CacheStore,
+{
+    /// Wrap `inner`, caching responses in `store`.
+    pub fn with_store(inner: C, store: S) -> Self {
+        Self {
+            inner,
+            store,
+            default_ttl: DEFAULT_TTL,
+            ttls: Vec::new(),
+        }
+    }
+
+    /// Time to live for responses without a more specific TTL (default: 60s).
+    /// A TTL of zero disables caching.
+    pub fn default_ttl(mut self, ttl: Duration) -> Self {
+        self.default_ttl = ttl;
+        self
+    }
+
+    /// Time to live for responses of endpoints whose path starts with
+    /// `prefix`, e.g. `"leaderboards"` or `"games"`. The longest matching
+    /// prefix wins. A TTL of zero disables caching for those endpoints.
+    pub fn ttl<P>(mut self, prefix: P, ttl: Duration) -> Self
+    where
+        P: Into<String>,
+    {
+        let prefix = prefix.into();
+        self.ttls
+            .push((prefix.trim_start_matches('/').to_owned(), ttl));
+        self
+    }
+
+    /// The wrapped client.
+    pub fn inner(&self) -> &C {
+        &self.inner
+    }
+
+    /// The store holding cached responses.
+    pub fn store(&self) -> &S {
+        &self.store
+    }
+
+    /// Unwrap the inner client, dropping the cache.
+    pub fn into_inner(self) -> C {
+        self.inner
+    }
+}
+
+impl<C, S> CachedClient<C, S>
+where
+    C: RestClient,
+    S: CacheStore,
+{
+    /// Returns the cache key and TTL for the request, or `None` if it must not
+    /// be cached.
+    fn cache_key(&self, request: &RequestBuilder) -> Option<(String, Duration)> {
+        if request.method_ref() != Some(&Method::GET) {
+            return None;
+        }
+        if request
+            .extensions_ref()
+            .is_none_or(|ext| ext.get::<api::Authenticated>().is_some())
+        {
+            return None;
+        }
+        let uri = request.uri_ref()?.to_string();
+        let ttl = self.ttl_for(&uri);
+        if ttl.is_zero() {
+            None
+        } else {
+            Some((uri, ttl))
+        }
+    }
+
+    fn ttl_for(&self, uri: &str) -> Duration {
+        let path = match (Url::parse(uri), self.inner.rest_endpoint("")) {
+            (Ok(url), Ok(base)) => url
+                .path()
+                .strip_prefix(base.path())
+                .unwrap_or_else(|| url.path())
+                .trim_start_matches('/')
+                .to_owned(),
+            _ => return self.default_ttl,
+        };
+        self.ttls
+            .iter()
+            .filter(|(prefix, _)| path.starts_with(prefix.as_str()))
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map_or(self.default_ttl, |(_, ttl)| *ttl)
+    }
+
+    /// Look up a cached response. Returns the response if it is still fresh,
+    /// otherwise adds validators for a conditional request.
+    fn lookup(
+        &self,
+        key: &str,
+        request: &mut RequestBuilder,
+    ) -> (Option<CachedResponse>, Option<Response<Bytes>>) {
+        let cached = match self.store.get(key) {
+            Some(cached) => cached,
+            None => return (None, None),
+        };
+        if cached.is_fresh() {
+            if let Ok(rsp) = cached.to_response() {
+                debug!("cache hit for {}", key);
+                return (Some(cached), Some(rsp));
+            }
+        }
+        if let Some(headers) = request.headers_mut() {
+            if let Some(etag) = cached.headers.get(header::ETAG) {
+                headers.insert(header::IF_NONE_MATCH, etag.clone());
+            }
+            if let Some(modified) = cached.headers.get(header::LAST_MODIFIED) {
+                headers.insert(header::IF_MODIFIED_SINCE, modified.clone());
+            }
+        }
+        (Some(cached), None)
+    }
+
+    /// Update the cache with a response from the inner client. Returns the
+    /// response to hand to the caller.
+    fn update(
+        &self,
+        key: &str,
+        ttl: Duration,
+        cached: Option<CachedResponse>,
+        rsp: Response<Bytes>,
+    ) -> Response<Bytes> {
+        let expires = SystemTime::now() + ttl;
+        if rsp.status() == StatusCode::NOT_MODIFIED {
+            if let Some(mut cached) = cached {
+                debug!("cache entry for {} revalidated", key);
+                cached.expires = expires;
+                if let Ok(rsp) = cached.to_response() {
+                    self.store.insert(key, cached);
+                    return rsp;
+                }
+            }
+        } else if rsp.status().is_success() {
+            self.store.insert(
+                key,
+                CachedResponse {
+                    status: rsp.status(),
+                    headers: rsp.headers().clone(),
+                    body: rsp.body().clone(),
+                    expires,
+                },
+            );
+        } else if cached.is_some() {
+            self.store.remove(key);
+        }
+        rsp
+    }
+}
+
+impl<C, S> RestClient for CachedClient<C, S>
+where
+    C: RestClient,
+{
+    type Error = C::Error;
+
+    fn rest_endpoint(&self, endpoint: &str) -> Result<Url, ApiError<Self::Error>> {
+        self.inner.rest_endpoint(endpoint)
+    }
+
+    fn has_api_key(&self) -> bool {
+        self.inner.has_api_key()
+    }
+
+    fn intercept_request(
+        &self,
+        request: &mut Request<Vec<u8>>,
+    ) -> Result<(), ApiError<Self::Error>> {
+        self.inner.intercept_request(request)
+    }
+
+    fn intercept_response(
+        &self,
+        request: &RequestInfo,
+        response: &mut Response<Bytes>,
+    ) -> Result<(), ApiError<Self::Error>> {
+        self.inner.intercept_response(request, response)
+    }
+}
+
+impl<C, S> Client for CachedClient<C, S>
+where
+    C: Client,
+    S: CacheStore,
+{
+    fn rest(
+        &self,
+        mut request: RequestBuilder,
+        body: Vec<u8>,
+    ) -> Result<Response<Bytes>, ApiError<Self::Error>> {
+        let (key, ttl) = match self.cache_key(&request) {
+            Some(key) => key,
+            None => return self.inner.rest(request, body),
+        };
+        let cached = match self.lookup(&key, &mut request) {
+            (_, Some(rsp)) => return Ok(rsp),
+            (cached, None) => cached,
+        };
+        let rsp = self.inner.rest(request, body)?;
+        Ok(self.update(&key, ttl, cached, rsp))
+    }
+}
+
+#[async_trait]
+impl<C, S> AsyncClient for CachedClient<C, S>
+where
+    C: AsyncClient + Sync,
+    S: CacheStore,
+{
+    async fn rest_async(
+        &self,
+        mut request: RequestBuilder,
+        body: Vec<u8>,
+    ) -> Result<Response<Bytes>, ApiError<Self::Error>> {
+        let (key, ttl) = match self.cache_key(&request) {
+            Some(key) => key,
+            None => return self.inner.rest_async(request, body).await,
+        };
+        let cached = match self.lookup(&key, &mut request) {
+            (_, Some(rsp)) => return Ok(rsp),
+            (cached, None) => cached,
+        };
+        let rsp = self.inner.rest_async(request, body).await?;
+        Ok(self.update(&key, ttl, cached, rsp))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use http::Method;
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        api::{games::Game, profile::Profile, Query},
+        mock::MockClient,
+    };
+
+    fn game() -> Game<'static> {
+        Game::builder().id("v1pxjz68").build().unwrap()
+    }
+
+    fn response(status: StatusCode, etag: &str, body: &str) -> Response<Bytes> {
+        Response::builder()
+            .status(status)
+            .header(header::ETAG, etag)
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Bytes::from(body.to_owned()))
+            .unwrap()
+    }
+
+    #[test]
+    fn fresh_responses_are_served_from_cache() {
+        let mock = MockClient::new();
+        mock.add_data(Method::GET, "games/v1pxjz68", json!({ "id": "v1pxjz68" }));
+        let client = CachedClient::new(mock);
+
+        for _ in 0..2 {
+            let game: serde_json::Value = game().query(&client).unwrap();
+            assert_eq!(game["id"], "v1pxjz68");
+        }
+        assert_eq!(client.inner().requests().len(), 1);
+    }
+
+    #[test]
+    fn stale_responses_are_revalidated() {
+        let mock = MockClient::new();
+        mock.add(
+            Method::GET,
+            "games/v1pxjz68",
+            response(StatusCode::OK, "\"v1\"", r#"{"data": {"id": "v1pxjz68"}}"#),
+        );
+        let client = CachedClient::new(mock).default_ttl(Duration::from_millis(1));
+
+        let _: serde_json::Value = game().query(&client).unwrap();
+        thread::sleep(Duration::from_millis(5));
+        client.inner().add(
+            Method::GET,
+            "games/v1pxjz68",
+            response(StatusCode::NOT_MODIFIED, "\"v1\"", ""),
+        );
+        let game: serde_json::Value = game().query(&client).unwrap();
+        assert_eq!(game["id"], "v1pxjz68");
+
+        let requests = client.inner().requests();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0].headers.get(header::IF_NONE_MATCH), None);
+        assert_eq!(
+            requests[1].headers.get(header::IF_NONE_MATCH).unwrap(),
+            "\"v1\""
+        );
+    }
+
+    #[test]
+    fn errors_evict_stale_responses() {
+        let mock = MockClient::new();
+        mock.add_data(Method::GET, "games/v1pxjz68", json!({ "id": "v1pxjz68" }));
+        let client = CachedClient::new(mock).default_ttl(Duration::from_millis(1));
+
+        let _: serde_json::Value = game().query(&client).unwrap();
+        thread::sleep(Duration::from_millis(5));
+        client.inner().add_json(
+            Method::GET,
+            "games/v1pxjz68",
+            StatusCode::NOT_FOUND,
+            json!({ "status": 404, "message": "not found" }),
+        );
+        let result: Result<serde_json::Value, _> = game().query(&client);
+        assert!(matches!(result, Err(ApiError::NotFound(_))));
+        assert!(client
+            .store()
+            .get(&client.inner().requests()[0].url)
+            .is_none());
+    }
+
+    #[test]
+    fn ttl_uses_longest_matching_prefix() {
+        let client = CachedClient::new(MockClient::new())
+            .default_ttl(Duration::from_secs(1))
+            .ttl("games", Duration::from_secs(2))
+            .ttl("/games/v1pxjz68/categories", Duration::from_secs(3));
+        let base = "https://www.speedrun.com/api/v1/";
+        assert_eq!(
+            client.ttl_for(&format!("{}runs", base)),
+            Duration::from_secs(1)
+        );
+        assert_eq!(
+            client.ttl_for(&format!("{}games/v1pxjz68", base)),
+            Duration::from_secs(2)
+        );
+        assert_eq!(
+            client.ttl_for(&format!("{}games/v1pxjz68/categories", base)),
+            Duration::from_secs(3)
+        );
+    }
+
+    #[test]
+    fn authenticated_requests_are_not_cached() {
+        let mock = MockClient::new().authenticated(true);
+        mock.add_data(Method::GET, "profile", json!({ "id": "me" }));
+        let client = CachedClient::new(mock);
+
+        for _ in 0..2 {
+            let _: serde_json::Value = Profile::builder().build().unwrap().query(&client).unwrap();
+        }
+        assert_eq!(client.inner().requests().len(), 2);
+    }
+}
@@ -1,18 +1,25 @@
-use std::convert::TryInto;
+use std::{convert::TryInto, thread};
 
+#[cfg(feature = "async")]
 use async_trait::async_trait;
+#[cfg(feature = "async")]
 use futures::TryFutureExt;
 use log::debug;
-use reqwest::{blocking::Client as HttpClient, Client as AsyncHttpClient};
+use reqwest::blocking::Client as HttpClient;
+#[cfg(feature = "async")]
+use reqwest::Client as AsyncHttpClient;
 use url::Url;
 
 use crate::{
     api,
     auth::Auth,
     error::{RestError, SpeedrunApiResult},
+    middleware::{Middleware, MiddlewareChain, RequestInfo},
+    rate_limit::{RateLimitMetrics, RateLimiter},
+    retry::{Attempts, RetryPolicy},
 };
 
-const SPEEDRUN_API_BASE_URL: &str = "https://www.speedrun.com/api/v1/";
+pub(crate) const SPEEDRUN_API_BASE_URL: &str = "https://www.speedrun.com/api/v1/";
 
 /// A client for communicating with the Speedrun.com API
 #[derive(Clone, Debug)]
@@ -20,12 +27,15 @@ pub struct SpeedrunApiClient {
     client: HttpClient,
     rest_url: Url,
     api_key: Auth,
+    rate_limit: Option<RateLimiter>,
+    retry: Option<RetryPolicy>,
+    middleware: MiddlewareChain,
 }
 
 impl SpeedrunApiClient {
     /// Create a new Speedrun.com API client.
     pub fn new() -> SpeedrunApiResult<Self> {
-        Self::new_impl::<String>(None)
+        Self::new_impl(&SpeedrunApiBuilder::new())
     }
 
     /// Create a new Speedrun.com API client, with the provided API key.
@@ -33,22 +43,22 @@ impl SpeedrunApiClient {
     where
         S: Into<String>,
     {
-        Self::new_impl(Some(api_key))
+        Self::new_impl(SpeedrunApiBuilder::new().api_key(api_key))
     }
 
-    fn new_impl<S>(api_key: Option<S>) -> SpeedrunApiResult<Self>
-    where
-        S: Into<String>,
-    {
+    fn new_impl(builder: &SpeedrunApiBuilder) -> SpeedrunApiResult<Self> {
         let rest_url = Url::parse(SPEEDRUN_API_BASE_URL)?;
         let api_key = Auth {
-            token: api_key.map(Into::into),
+            token: builder.api_key.clone(),
         };
 
         Ok(SpeedrunApiClient {
             client: HttpClient::new(),
             rest_url,
             api_key,
+            rate_limit: builder.rate_limit.clone(),
+            retry: builder.retry.clone(),
+            middleware: builder.middleware.clone(),
         })
     }
 
@@ -56,6 +66,47 @@ impl SpeedrunApiClient {
     pub fn builder() -> SpeedrunApiBuilder {
         SpeedrunApiBuilder::new()
     }
+
+    /// Metrics for the rate limiter used by this client, if one is configured.
+    pub fn rate_limit_metrics(&self) -> Option<RateLimitMetrics> {
+        self.rate_limit.as_ref().map(RateLimiter::metrics)
+    }
+
+    fn send(
+        &self,
+        mut request: reqwest::blocking::Request,
+    ) -> Result<reqwest::blocking::Response, reqwest::Error> {
+        let mut attempts = Attempts::new(
+            self.rate_limit.as_ref(),
+            self.retry.as_ref(),
+            request.method(),
+        );
+        loop {
+            let next = if attempts.can_retry() {
+                request.try_clone()
+            } else {
+                None
+            };
+            let wait = attempts.wait();
+            if !wait.is_zero() {
+                debug!("rate limit: waiting {:?}", wait);
+                thread::sleep(wait);
+            }
+            let rsp = self.client.execute(request);
+            let delay = match (&rsp, &next) {
+                (_, None) => None,
+                (Ok(rsp), Some(_)) => attempts.retry_response(rsp.status(), rsp.headers()),
+                (Err(err), Some(_)) => attempts.retry_error(err),
+            };
+            match (delay, next) {
+                (Some(delay), Some(next)) => {
+                    thread::sleep(delay);
+                    request = next;
+                }
+                _ => return rsp,
+            }
+        }
+    }
 }
 
 impl api::RestClient for SpeedrunApiClient {
@@ -71,6 +122,23 @@ impl api::RestClient for SpeedrunApiClient {
     fn has_api_key(&self) -> bool {
         self.api_key.token.is_some()
     }
+
+    fn intercept_request(
+        &self,
+        request: &mut http::Request<Vec<u8>>,
+    ) -> Result<(), api::ApiError<Self::Error>> {
+        self.middleware.on_request(request);
+        Ok(())
+    }
+
+    fn intercept_response(
+        &self,
+        request: &RequestInfo,
+        response: &mut http::Response<bytes::Bytes>,
+    ) -> Result<(), api::ApiError<Self::Error>> {
+        self.middleware.on_response(request, response);
+        Ok(())
+    }
 }
 
 impl api::Client for SpeedrunApiClient {
@@ -82,9 +150,8 @@ impl api::Client for SpeedrunApiClient {
         let call = || -> Result<_, RestError> {
             self.api_key
                 .set_auth_header(request.headers_mut().unwrap())?;
-            let http_request = request.body(body)?;
-            let request = http_request.try_into()?;
-            let rsp = self.client.execute(request)?;
+            let request = request.body(body)?.try_into()?;
+            let rsp = self.send(request)?;
 
             let mut http_rsp = http::Response::builder()
                 .status(rsp.status())
@@ -93,24 +160,30 @@ impl api::Client for SpeedrunApiClient {
             for (key, val) in rsp.headers() {
                 headers.insert(key, val.clone());
             }
-            http_rsp.body(rsp.bytes()?).map_err(From::from)
+            let http_rsp = http_rsp.body(rsp.bytes()?)?;
+            Ok(http_rsp)
         };
         call().map_err(api::ApiError::client)
     }
 }
 
 /// An asynchronous client for communicating with the Speedrun.com API
+#[cfg(feature = "async")]
 #[derive(Clone, Debug)]
 pub struct SpeedrunApiClientAsync {
     client: AsyncHttpClient,
     rest_url: Url,
     api_key: Auth,
+    rate_limit: Option<RateLimiter>,
+    retry: Option<RetryPolicy>,
+    middleware: MiddlewareChain,
 }
 
+#[cfg(feature = "async")]
 impl SpeedrunApiClientAsync {
     /// Create a new asynchronous Speedrun.com API client
     pub fn new() -> SpeedrunApiResult<Self> {
-        Self::new_impl::<String>(None)
+        Self::new_impl(&SpeedrunApiBuilder::new())
     }
 
     /// Create a new asynchronous Speedrun.com API client, with the provided API
@@ -119,22 +192,22 @@ impl SpeedrunApiClientAsync {
     where
         S: Into<String>,
     {
-        Self::new_impl(Some(api_key.into()))
+        Self::new_impl(SpeedrunApiBuilder::new().api_key(api_key))
     }
 
-    fn new_impl<S>(api_key: Option<S>) -> SpeedrunApiResult<Self>
-    where
-        S: Into<String>,
-    {
+    fn new_impl(builder: &SpeedrunApiBuilder) -> SpeedrunApiResult<Self> {
         let rest_url = Url::parse(SPEEDRUN_API_BASE_URL)?;
         let client = AsyncHttpClient::new();
         let auth = Auth {
-            token: api_key.map(Into::into),
+            token: builder.api_key.clone(),
         };
         let api = Self {
             client,
             rest_url,
             api_key: auth,
+            rate_limit: builder.rate_limit.clone(),
+            retry: builder.retry.clone(),
+            middleware: builder.middleware.clone(),
         };
         Ok(api)
     }
@@ -143,8 +216,50 @@ impl SpeedrunApiClientAsync {
     pub fn builder() -> SpeedrunApiBuilder {
         SpeedrunApiBuilder::new()
     }
+
+    /// Metrics for the rate limiter used by this client, if one is configured.
+    pub fn rate_limit_metrics(&self) -> Option<RateLimitMetrics> {
+        self.rate_limit.as_ref().map(RateLimiter::metrics)
+    }
+
+    async fn send(
+        &self,
+        mut request: reqwest::Request,
+    ) -> Result<reqwest::Response, reqwest::Error> {
+        let mut attempts = Attempts::new(
+            self.rate_limit.as_ref(),
+            self.retry.as_ref(),
+            request.method(),
+        );
+        loop {
+            let next = if attempts.can_retry() {
+                request.try_clone()
+            } else {
+                None
+            };
+            let wait = attempts.wait();
+            if !wait.is_zero() {
+                debug!("rate limit: waiting {:?}", wait);
+                tokio::time::sleep(wait).await;
+            }
+            let rsp = self.client.execute(request).await;
+            let delay = match (&rsp, &next) {
+                (_, None) => None,
+                (Ok(rsp), Some(_)) => attempts.retry_response(rsp.status(), rsp.headers()),
+                (Err(err), Some(_)) => attempts.retry_error(err),
+            };
+            match (delay, next) {
+                (Some(delay), Some(next)) => {
+                    tokio::time::sleep(delay).await;
+                    request = next;
+                }
+                _ => return rsp,
+            }
+        }
+    }
 }
 
+#[cfg(feature = "async")]
 impl api::RestClient for SpeedrunApiClientAsync {
     type Error = RestError;
 
@@ -158,8 +273,26 @@ impl api::RestClient for SpeedrunApiClientAsync {
     fn has_api_key(&self) -> bool {
         self.api_key.token.is_some()
     }
+
+    fn intercept_request(
+        &self,
+        request: &mut http::Request<Vec<u8>>,
+    ) -> Result<(), api::ApiError<Self::Error>> {
+        self.middleware.on_request(request);
+        Ok(())
+    }
+
+    fn intercept_response(
+        &self,
+        request: &RequestInfo,
+        response: &mut http::Response<bytes::Bytes>,
+    ) -> Result<(), api::ApiError<Self::Error>> {
+        self.middleware.on_response(request, response);
+        Ok(())
+    }
 }
 
+#[cfg(feature = "async")]
 #[async_trait]
 impl api::AsyncClient for SpeedrunApiClientAsync {
     async fn rest_async(
@@ -170,9 +303,8 @@ impl api::AsyncClient for SpeedrunApiClientAsync {
         let call = || async {
             self.api_key
                 .set_auth_header(request.headers_mut().unwrap())?;
-            let http_request = request.body(body)?;
-            let request = http_request.try_into()?;
-            let rsp = self.client.execute(request).await?;
+            let request = request.body(body)?.try_into()?;
+            let rsp = self.send(request).await?;
 
             let mut http_rsp = http::Response::builder()
                 .status(rsp.status())
@@ -181,7 +313,8 @@ impl api::AsyncClient for SpeedrunApiClientAsync {
             for (key, val) in rsp.headers() {
                 headers.insert(key, val.clone());
             }
-            http_rsp.body(rsp.bytes().await?).map_err(From::from)
+            let http_rsp = http_rsp.body(rsp.bytes().await?)?;
+            Ok(http_rsp)
         };
         call().map_err(api::ApiError::client).await
     }
@@ -191,6 +324,9 @@ impl api::AsyncClient for SpeedrunApiClientAsync {
 #[derive(Debug, Default)]
 pub struct SpeedrunApiBuilder {
     api_key: Option<String>,
+    rate_limit: Option<RateLimiter>,
+    retry: Option<RetryPolicy>,
+    middleware: MiddlewareChain,
 }
 
 impl SpeedrunApiBuilder {
@@ -208,13 +344,46 @@ impl SpeedrunApiBuilder {
         self
     }
 
+    /// Limit the rate at which requests are sent.
+    ///
+    /// All clients built by this builder share the same limiter. Use
+    /// [`RateLimiter::default()`] to stay within the 100 requests per minute
+    /// allowed by speedrun.com.
+    ///
+    /// The asynchronous client waits using `tokio::time`, so it must run on a
+    /// Tokio runtime with the time driver enabled.
+    pub fn rate_limit(&mut self, value: RateLimiter) -> &mut Self {
+        self.rate_limit = Some(value);
+        self
+    }
+
+    /// Retry requests that fail with a transient error, according to `policy`.
+    ///
+    /// Without a retry policy every request is attempted exactly once.
+    pub fn retry_policy(&mut self, policy: RetryPolicy) -> &mut Self {
+        self.retry = Some(policy);
+        self
+    }
+
+    /// Add middleware that is called around every request.
+    ///
+    /// See [`Middleware`] for the order middleware is called in.
+    pub fn middleware<M>(&mut self, middleware: M) -> &mut Self
+    where
+        M: Middleware + 'static,
+    {
+        self.middleware.push(middleware);
+        self
+    }
+
     /// Build a synchronous Speedrun.com API client.
     pub fn build(&self) -> SpeedrunApiResult<SpeedrunApiClient> {
-        SpeedrunApiClient::new_impl(self.api_key.as_ref())
+        SpeedrunApiClient::new_impl(self)
     }
 
     /// Build an asynchronous Speedrun.com API client.
+    #[cfg(feature = "async")]
     pub fn build_async(&self) -> SpeedrunApiResult<SpeedrunApiClientAsync> {
-        SpeedrunApiClientAsync::new_impl(self.api_key.as_ref())
+        SpeedrunApiClientAsync::new_impl(self)
     }
 }
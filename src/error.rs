@@ -13,6 +13,9 @@ pub enum SpeedrunApiError {
     /// Error from the speedrun.com API
     #[error("API error: {0}")]
     Api(#[from] api::ApiError<RestError>),
+    /// Error exporting the game catalog
+    #[error("export error: {0}")]
+    Export(#[from] api::games::ExportError<RestError>),
     /// Error parsing URL
     #[error("url parse error: {0}")]
     Parse(#[from] url::ParseError),
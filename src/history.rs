@@ -0,0 +1,914 @@
+//! Record histories.
+//!
+//! [`WorldRecordHistory`] lists the world records of a leaderboard in the
+//! order they were set. It is built from the [`Runs`] endpoint, so it only
+//! needs one paginated request (and two for the game's ruleset and the
+//! leaderboard's timing method, unless a [`RunComparator`] is given, and one
+//! for the game's variables if subcategory values are set).
+//!
+//! [`PersonalBestHistory`] lists the personal bests of a user for every
+//! leaderboard they submitted runs to, along with their placement at the time.
+//!
+//! # Example
+//!
+//! ```rust ,no_run
+//! use speedrun_api::{
+//!     history::{PersonalBestHistory, WorldRecordHistory},
+//!     SpeedrunApiBuilder,
+//! };
+//!
+//! # fn main() -> speedrun_api::error::SpeedrunApiResult<()> {
+//! let client = SpeedrunApiBuilder::new().build()?;
+//! let history = WorldRecordHistory::builder()
+//!     .game("v1pxjz68")
+//!     .category("n2y3r8do")
+//!     .build()
+//!     .unwrap();
+//! for record in history.query(&client)? {
+//!     println!(
+//!         "{:?}: {} (improved by {:?})",
+//!         record.run.date, record.time, record.improvement
+//!     );
+//! }
+//!
+//! let history = PersonalBestHistory::builder().user("zx7gd1yx").build().unwrap();
+//! for progression in history.query(&client)? {
+//!     println!(
+//!         "{} {}: saved {}",
+//!         progression.game,
+//!         progression.category,
+//!         progression.time_saved()
+//!     );
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`Runs`]: crate::api::runs::Runs
+use std::{collections::HashMap, convert::TryFrom, time::Duration};
+
+use futures::TryStreamExt;
+
+use crate::{
+    api::{
+        categories::CategoryId,
+        games::{Game, GameId, GameVariables},
+        leaderboards::{FullGameLeaderboard, IndividualLevelLeaderboard, Leaderboard},
+        levels::LevelId,
+        runs::{RunId, RunStatus, Runs, RunsSorting},
+        users::UserId,
+        variables::{ValueId, VariableId},
+        ApiError, AsyncClient, AsyncQuery, Client, Direction, PageOptions, PagedEndpointExt, Query,
+    },
+    ranking::{self, RunComparator},
+    types::{self, Date, Player, Run, RunTime, Variable},
+};
+
+/// A world record, as part of a [`WorldRecordHistory`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct WorldRecord<'a> {
+    /// The record run.
+    pub run: Run<'a>,
+    /// The time of the run, as it is ranked on the leaderboard.
+    pub time: RunTime,
+    /// The record this run beat, if any.
+    pub previous_run: Option<RunId<'a>>,
+    /// The players holding the previous record.
+    pub previous_players: Vec<Player<'a>>,
+    /// How much faster this run is than the previous record.
+    pub improvement: Option<RunTime>,
+    /// The date this record was beaten, or `None` for the current record.
+    pub until: Option<Date>,
+}
+
+impl WorldRecord<'_> {
+    /// How long the record stood, or `None` for the current record.
+    pub fn stood_for(&self) -> Option<Duration> {
+        let since = self.run.date?.and_midnight().timestamp();
+        let until = self.until?.and_midnight().timestamp();
+        u64::try_from(until - since).ok().map(Duration::from_secs)
+    }
+}
+
+/// The world record progression of a leaderboard.
+///
+/// Only verified runs with a date are considered. A run is a new world record
+/// when it is faster than the record at the time it was played; runs that tie
+/// the record do not replace it.
+#[derive(Debug, Builder, Clone)]
+#[builder(setter(into, strip_option))]
+pub struct WorldRecordHistory<'a> {
+    #[doc = r"Game `ID` or abbreviation."]
+    game: GameId<'a>,
+    #[doc = r"Category `ID` or abbreviation."]
+    category: CategoryId<'a>,
+    #[doc = r"Level `ID`, for individual-level leaderboards."]
+    #[builder(default)]
+    level: Option<LevelId<'a>>,
+    #[doc = r"How runs are compared. Defaults to the way the leaderboard ranks runs, which requires two additional requests."]
+    #[builder(default)]
+    comparator: Option<RunComparator>,
+    #[builder(setter(name = "_variables"), private, default)]
+    variables: HashMap<VariableId<'a>, ValueId<'a>>,
+}
+
+impl<'a> WorldRecordHistory<'a> {
+    /// Create a builder for this history.
+    pub fn builder() -> WorldRecordHistoryBuilder<'a> {
+        WorldRecordHistoryBuilder::default()
+    }
+
+    /// Build the history from runs that were already fetched.
+    ///
+    /// Runs that do not belong to the leaderboard are ignored. `variables`
+    /// are the variables of the game, used for runs that have no value for a
+    /// subcategory; runs without a value never match if it is empty.
+    pub fn records<'r, I>(
+        &self,
+        comparator: &RunComparator,
+        variables: &[Variable<'_>],
+        runs: I,
+    ) -> Vec<WorldRecord<'r>>
+    where
+        I: IntoIterator<Item = Run<'r>>,
+    {
+        let mut runs: Vec<_> = runs
+            .into_iter()
+            .filter(|run| self.matches(variables, run) && run.date.is_some())
+            .collect();
+        runs.sort_by(|a, b| a.date.cmp(&b.date).then(a.submitted.cmp(&b.submitted)));
+
+        let mut records: Vec<WorldRecord<'r>> = Vec::new();
+        for run in runs {
+            let time = match comparator.ranked_time(&run.times) {
+                Some(time) => time,
+                None => continue,
+            };
+            let previous = records.last_mut();
+            if previous
+                .as_ref()
+                .is_some_and(|previous| time >= previous.time)
+            {
+                continue;
+            }
+            let (previous_run, previous_players, improvement) = match previous {
+                Some(previous) => {
+                    previous.until = run.date;
+                    (
+                        Some(previous.run.id.clone()),
+                        previous.run.players.clone(),
+                        Some(previous.time - time),
+                    )
+                }
+                None => (None, Vec::new(), None),
+            };
+            records.push(WorldRecord {
+                run,
+                time,
+                previous_run,
+                previous_players,
+                improvement,
+                until: None,
+            });
+        }
+        records
+    }
+
+    /// Retrieve the history using a synchronous client.
+    pub fn query<C>(&self, client: &C) -> Result<Vec<WorldRecord<'static>>, ApiError<C::Error>>
+    where
+        C: Client,
+    {
+        let comparator = match self.comparator {
+            Some(comparator) => comparator,
+            None => {
+                let game: types::Game<'_> = game_endpoint(&self.game).query(client)?;
+                let leaderboard: types::Leaderboard<'_> =
+                    self.leaderboard_endpoint().query(client)?;
+                RunComparator::for_leaderboard(&game.ruleset, &leaderboard)
+            }
+        };
+        let variables: Vec<Variable<'_>> = if self.variables.is_empty() {
+            Vec::new()
+        } else {
+            variables_endpoint(&self.game).query(client)?
+        };
+        let runs = self
+            .runs_endpoint()
+            .iter_with(client, PageOptions::largest_pages())
+            .collect::<Result<Vec<Run<'static>>, _>>()?;
+        Ok(self.records(&comparator, &variables, runs))
+    }
+
+    /// Retrieve the history using an asynchronous client.
+    pub async fn query_async<C>(
+        &self,
+        client: &C,
+    ) -> Result<Vec<WorldRecord<'static>>, ApiError<C::Error>>
+    where
+        C: AsyncClient + Sync,
+    {
+        let comparator = match self.comparator {
+            Some(comparator) => comparator,
+            None => {
+                let game: types::Game<'_> = game_endpoint(&self.game).query_async(client).await?;
+                let leaderboard: types::Leaderboard<'_> =
+                    self.leaderboard_endpoint().query_async(client).await?;
+                RunComparator::for_leaderboard(&game.ruleset, &leaderboard)
+            }
+        };
+        let variables: Vec<Variable<'_>> = if self.variables.is_empty() {
+            Vec::new()
+        } else {
+            variables_endpoint(&self.game).query_async(client).await?
+        };
+        let endpoint = self.runs_endpoint();
+        let runs: Vec<Run<'static>> = endpoint
+            .stream_with(client, PageOptions::largest_pages())
+            .try_collect()
+            .await?;
+        Ok(self.records(&comparator, &variables, runs))
+    }
+
+    /// The leaderboard's top run, only fetched for its timing method.
+    fn leaderboard_endpoint(&self) -> Leaderboard<'a> {
+        let variables = self
+            .variables
+            .iter()
+            .map(|(variable, value)| (variable.clone(), value.clone()));
+        match &self.level {
+            Some(level) => IndividualLevelLeaderboard::builder()
+                .game(self.game.clone())
+                .level(level.clone())
+                .category(self.category.clone())
+                .top(1)
+                .variables(variables)
+                .build()
+                .expect("all required fields are set")
+                .into(),
+            None => FullGameLeaderboard::builder()
+                .game(self.game.clone())
+                .category(self.category.clone())
+                .top(1)
+                .variables(variables)
+                .build()
+                .expect("all required fields are set")
+                .into(),
+        }
+    }
+
+    fn runs_endpoint(&self) -> Runs<'a> {
+        let mut builder = Runs::builder();
+        builder
+            .game(self.game.clone())
+            .category(self.category.clone())
+            .status(RunStatus::Verified)
+            .orderby(RunsSorting::Date)
+            .direction(Direction::Asc);
+        if let Some(level) = &self.level {
+            builder.level(level.clone());
+        }
+        builder.build().expect("all required fields are set")
+    }
+
+    fn matches(&self, variables: &[Variable<'_>], run: &Run<'_>) -> bool {
+        // The level itself is filtered by the API, and may have been given as
+        // an abbreviation.
+        run.level.is_some() == self.level.is_some()
+            && matches!(run.status, types::Status::Verified { .. })
+            && self.variables.iter().all(|(variable, value)| {
+                let run_value = run.values.get(variable).cloned().or_else(|| {
+                    let definition = variables.iter().find(|other| other.id == *variable)?;
+                    definition.values.default.as_ref().map(ToString::to_string)
+                });
+                run_value.is_some_and(|run_value| run_value == value.to_string())
+            })
+    }
+}
+
+impl<'a> WorldRecordHistoryBuilder<'a> {
+    /// Add a single subcategory variable to select the leaderboard.
+    pub fn variable<Var, Val>(&mut self, variable: Var, value: Val) -> &mut Self
+    where
+        Var: Into<VariableId<'a>>,
+        Val: Into<ValueId<'a>>,
+    {
+        self.variables
+            .get_or_insert_with(HashMap::new)
+            .insert(variable.into(), value.into());
+        self
+    }
+
+    /// Add multiple subcategory variables to select the leaderboard.
+    pub fn variables<I, Var, Val>(&mut self, iter: I) -> &mut Self
+    where
+        I: IntoIterator<Item = (Var, Val)>,
+        Var: Into<VariableId<'a>>,
+        Val: Into<ValueId<'a>>,
+    {
+        self.variables
+            .get_or_insert_with(HashMap::new)
+            .extend(iter.into_iter().map(|(k, v)| (k.into(), v.into())));
+        self
+    }
+}
+
+/// A personal best, as part of a [`PersonalBestProgression`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct PersonalBest<'a> {
+    /// The personal best run.
+    pub run: Run<'a>,
+    /// The time of the run, as it is ranked on the leaderboard.
+    pub time: RunTime,
+    /// The personal best this run superseded, if any.
+    pub previous_run: Option<RunId<'a>>,
+    /// The run that superseded this personal best, or `None` for the current
+    /// personal best.
+    pub superseded_by: Option<RunId<'a>>,
+    /// How much faster this run is than the previous personal best.
+    pub improvement: Option<RunTime>,
+    /// The place of the run on the leaderboard at the date it was played, if
+    /// it was retrieved.
+    pub place: Option<i64>,
+}
+
+/// The personal best progression of a user on a single leaderboard.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PersonalBestProgression<'a> {
+    /// The game of the leaderboard.
+    pub game: GameId<'a>,
+    /// The category of the leaderboard.
+    pub category: CategoryId<'a>,
+    /// The level of the leaderboard, for individual-level leaderboards.
+    pub level: Option<LevelId<'a>>,
+    /// The subcategory values of the leaderboard.
+    pub values: HashMap<VariableId<'a>, String>,
+    /// The personal bests, oldest first.
+    pub personal_bests: Vec<PersonalBest<'a>>,
+}
+
+impl<'a> PersonalBestProgression<'a> {
+    /// The current personal best.
+    pub fn current(&self) -> Option<&PersonalBest<'a>> {
+        self.personal_bests.last()
+    }
+
+    /// The time saved between the first and the current personal best.
+    pub fn time_saved(&self) -> RunTime {
+        match (self.personal_bests.first(), self.personal_bests.last()) {
+            (Some(first), Some(last)) => first.time - last.time,
+            _ => RunTime::ZERO,
+        }
+    }
+
+    fn leaderboard(
+        &self,
+        personal_best: &PersonalBest<'a>,
+        timing: types::TimingMethod,
+    ) -> Leaderboard<'a> {
+        let variables = self
+            .values
+            .iter()
+            .map(|(variable, value)| (variable.clone(), value.clone()));
+        match &self.level {
+            Some(level) => {
+                let mut builder = IndividualLevelLeaderboard::builder();
+                builder
+                    .game(self.game.clone())
+                    .level(level.clone())
+                    .category(self.category.clone())
+                    .timing(timing)
+                    .variables(variables);
+                if let Some(date) = personal_best.run.date {
+                    builder.date(date);
+                }
+                builder.build().expect("all required fields are set").into()
+            }
+            None => {
+                let mut builder = FullGameLeaderboard::builder();
+                builder
+                    .game(self.game.clone())
+                    .category(self.category.clone())
+                    .timing(timing)
+                    .variables(variables);
+                if let Some(date) = personal_best.run.date {
+                    builder.date(date);
+                }
+                builder.build().expect("all required fields are set").into()
+            }
+        }
+    }
+}
+
+/// The personal best progression of a user.
+///
+/// Only verified runs with a date are considered. Runs are grouped by
+/// leaderboard (game, category, level and subcategory values), and a run is a
+/// new personal best when it is faster than the user's previous best on that
+/// leaderboard.
+///
+/// Retrieving the history takes one paginated request for the runs of the
+/// user, and two requests for the ruleset and variables of every game. When
+/// placements are enabled, there is one more request for every personal best.
+#[derive(Debug, Builder, Clone)]
+#[builder(setter(into, strip_option))]
+pub struct PersonalBestHistory<'a> {
+    #[doc = r"User `ID`."]
+    user: UserId<'a>,
+    #[doc = r"Only return personal bests for `game`."]
+    #[builder(default)]
+    game: Option<GameId<'a>>,
+    #[doc = r"Retrieve the placement of every personal best. (default: `true`)"]
+    #[builder(default = "true")]
+    placements: bool,
+}
+
+impl<'a> PersonalBestHistory<'a> {
+    /// Create a builder for this history.
+    pub fn builder() -> PersonalBestHistoryBuilder<'a> {
+        PersonalBestHistoryBuilder::default()
+    }
+
+    /// Build the progressions from runs of a single game that were already
+    /// fetched, using the variables of the game to find the subcategories.
+    ///
+    /// Placements are not set.
+    pub fn progressions<'r, I>(
+        comparator: &RunComparator,
+        variables: &[Variable<'r>],
+        runs: I,
+    ) -> Vec<PersonalBestProgression<'r>>
+    where
+        I: IntoIterator<Item = Run<'r>>,
+    {
+        let mut runs: Vec<_> = runs
+            .into_iter()
+            .filter(|run| {
+                run.date.is_some() && matches!(run.status, types::Status::Verified { .. })
+            })
+            .collect();
+        runs.sort_by(|a, b| a.date.cmp(&b.date).then(a.submitted.cmp(&b.submitted)));
+
+        let mut progressions: Vec<PersonalBestProgression<'r>> = Vec::new();
+        for run in runs {
+            let time = match comparator.ranked_time(&run.times) {
+                Some(time) => time,
+                None => continue,
+            };
+            let values: HashMap<_, _> = ranking::subcategory(variables, &run).into_iter().collect();
+            let index = match progressions.iter().position(|progression| {
+                progression.game == run.game
+                    && progression.category == run.category
+                    && progression.level == run.level
+                    && progression.values == values
+            }) {
+                Some(index) => index,
+                None => {
+                    progressions.push(PersonalBestProgression {
+                        game: run.game.clone(),
+                        category: run.category.clone(),
+                        level: run.level.clone(),
+                        values,
+                        personal_bests: Vec::new(),
+                    });
+                    progressions.len() - 1
+                }
+            };
+
+            let personal_bests = &mut progressions[index].personal_bests;
+            let previous = personal_bests.last_mut();
+            if previous
+                .as_ref()
+                .is_some_and(|previous| time >= previous.time)
+            {
+                continue;
+            }
+            let (previous_run, improvement) = match previous {
+                Some(previous) => {
+                    previous.superseded_by = Some(run.id.clone());
+                    (Some(previous.run.id.clone()), Some(previous.time - time))
+                }
+                None => (None, None),
+            };
+            personal_bests.push(PersonalBest {
+                run,
+                time,
+                previous_run,
+                superseded_by: None,
+                improvement,
+                place: None,
+            });
+        }
+        progressions
+    }
+
+    /// Retrieve the history using a synchronous client.
+    pub fn query<C>(
+        &self,
+        client: &C,
+    ) -> Result<Vec<PersonalBestProgression<'static>>, ApiError<C::Error>>
+    where
+        C: Client,
+    {
+        let runs = self
+            .runs_endpoint()
+            .iter_with(client, PageOptions::largest_pages())
+            .collect::<Result<Vec<Run<'static>>, _>>()?;
+
+        let mut out = Vec::new();
+        for (game, runs) in group_by_game(runs) {
+            let ruleset: types::Game<'_> = game_endpoint(&game).query(client)?;
+            let comparator = RunComparator::from_ruleset(&ruleset.ruleset);
+            let variables: Vec<Variable<'static>> = variables_endpoint(&game).query(client)?;
+            let mut progressions = Self::progressions(&comparator, &variables, runs);
+            if self.placements {
+                for progression in &mut progressions {
+                    for index in 0..progression.personal_bests.len() {
+                        let personal_best = &progression.personal_bests[index];
+                        let leaderboard: types::Leaderboard<'_> = progression
+                            .leaderboard(personal_best, comparator.timing())
+                            .query(client)?;
+                        let place = place(&comparator, &leaderboard, personal_best);
+                        progression.personal_bests[index].place = Some(place);
+                    }
+                }
+            }
+            out.extend(progressions);
+        }
+        Ok(out)
+    }
+
+    /// Retrieve the history using an asynchronous client.
+    pub async fn query_async<C>(
+        &self,
+        client: &C,
+    ) -> Result<Vec<PersonalBestProgression<'static>>, ApiError<C::Error>>
+    where
+        C: AsyncClient + Sync,
+    {
+        let endpoint = self.runs_endpoint();
+        let runs: Vec<Run<'static>> = endpoint
+            .stream_with(client, PageOptions::largest_pages())
+            .try_collect()
+            .await?;
+
+        let mut out = Vec::new();
+        for (game, runs) in group_by_game(runs) {
+            let ruleset: types::Game<'_> = game_endpoint(&game).query_async(client).await?;
+            let comparator = RunComparator::from_ruleset(&ruleset.ruleset);
+            let variables: Vec<Variable<'static>> =
+                variables_endpoint(&game).query_async(client).await?;
+            let mut progressions = Self::progressions(&comparator, &variables, runs);
+            if self.placements {
+                for progression in &mut progressions {
+                    for index in 0..progression.personal_bests.len() {
+                        let personal_best = &progression.personal_bests[index];
+                        let leaderboard: types::Leaderboard<'_> = progression
+                            .leaderboard(personal_best, comparator.timing())
+                            .query_async(client)
+                            .await?;
+                        let place = place(&comparator, &leaderboard, personal_best);
+                        progression.personal_bests[index].place = Some(place);
+                    }
+                }
+            }
+            out.extend(progressions);
+        }
+        Ok(out)
+    }
+
+    fn runs_endpoint(&self) -> Runs<'a> {
+        let mut builder = Runs::builder();
+        builder
+            .user(self.user.clone())
+            .status(RunStatus::Verified)
+            .orderby(RunsSorting::Date)
+            .direction(Direction::Asc);
+        if let Some(game) = &self.game {
+            builder.game(game.clone());
+        }
+        builder.build().expect("all required fields are set")
+    }
+}
+
+/// Group runs by game, ordered by game `ID`.
+fn group_by_game<'r>(runs: Vec<Run<'r>>) -> Vec<(GameId<'r>, Vec<Run<'r>>)> {
+    let mut games: Vec<(GameId<'r>, Vec<Run<'r>>)> = Vec::new();
+    for run in runs {
+        match games.iter_mut().find(|(game, _)| *game == run.game) {
+            Some((_, runs)) => runs.push(run),
+            None => games.push((run.game.clone(), vec![run])),
+        }
+    }
+    games.sort_by_cached_key(|(game, _)| game.to_string());
+    games
+}
+
+fn game_endpoint<'a>(game: &GameId<'a>) -> Game<'a> {
+    Game::builder()
+        .id(game.clone())
+        .build()
+        .expect("all required fields are set")
+}
+
+fn variables_endpoint<'a>(game: &GameId<'a>) -> GameVariables<'a> {
+    GameVariables::builder()
+        .id(game.clone())
+        .build()
+        .expect("all required fields are set")
+}
+
+/// The place a personal best would have had on `leaderboard`.
+fn place(
+    comparator: &RunComparator,
+    leaderboard: &types::Leaderboard<'_>,
+    personal_best: &PersonalBest<'_>,
+) -> i64 {
+    let faster = leaderboard
+        .runs
+        .iter()
+        .filter(|ranked| ranked.run.id != personal_best.run.id)
+        .filter(|ranked| {
+            comparator.compare_times(&ranked.run.times, &personal_best.run.times)
+                == std::cmp::Ordering::Less
+        })
+        .count();
+    faster as i64 + 1
+}
+
+#[cfg(test)]
+mod tests {
+    use http::Method;
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        mock::MockClient,
+        test_utils::{
+            game_json, page, run, run_json, run_with, times_json, variable, variable_json,
+        },
+        types::TimingMethod,
+    };
+
+    fn comparator() -> RunComparator {
+        RunComparator::new(TimingMethod::Realtime, true)
+    }
+
+    fn history() -> WorldRecordHistory<'static> {
+        WorldRecordHistory::builder()
+            .game("o1y9wo6q")
+            .category("wkpoo02r")
+            .comparator(comparator())
+            .build()
+            .unwrap()
+    }
+
+    fn ids<'a, I: IntoIterator<Item = &'a Run<'a>>>(runs: I) -> Vec<String> {
+        runs.into_iter().map(|run| run.id.to_string()).collect()
+    }
+
+    #[test]
+    fn only_faster_runs_beat_the_record() {
+        let runs = vec![
+            run("first", Some(70.0), Some("2020-01-01")),
+            run("tie", Some(70.0), Some("2020-02-01")),
+            run("second", Some(65.0), Some("2020-03-01")),
+            run("slower", Some(66.0), Some("2020-04-01")),
+            run("undated", Some(50.0), None),
+            run_with(
+                "rejected",
+                Some(50.0),
+                Some("2020-05-01"),
+                json!({ "status": { "status": "rejected", "examiner": "x", "reason": "" } }),
+            ),
+        ];
+        let records = history().records(&comparator(), &[], runs);
+        assert_eq!(ids(records.iter().map(|r| &r.run)), ["first", "second"]);
+
+        let (first, second) = (&records[0], &records[1]);
+        assert_eq!(first.until, Some(Date::new(2020, 3, 1).unwrap()));
+        assert_eq!(first.stood_for(), Some(Duration::from_secs(60 * 86_400)));
+        assert_eq!(second.previous_run, Some(RunId::new("first")));
+        assert_eq!(second.improvement, Some(RunTime::from_millis(5000)));
+        assert_eq!(second.until, None);
+        assert_eq!(second.stood_for(), None);
+    }
+
+    #[test]
+    fn runs_without_a_value_use_the_default() {
+        let history = WorldRecordHistory::builder()
+            .game("o1y9wo6q")
+            .category("wkpoo02r")
+            .variable("sub", "a")
+            .build()
+            .unwrap();
+        let runs = || {
+            vec![
+                run_with(
+                    "a",
+                    Some(70.0),
+                    Some("2020-01-01"),
+                    json!({ "values": { "sub": "a" } }),
+                ),
+                run("default", Some(65.0), Some("2020-02-01")),
+                run_with(
+                    "b",
+                    Some(60.0),
+                    Some("2020-03-01"),
+                    json!({ "values": { "sub": "b" } }),
+                ),
+            ]
+        };
+        let variables = [variable("sub", true, false, json!({ "type": "global" }))];
+
+        let records = history.records(&comparator(), &variables, runs());
+        assert_eq!(ids(records.iter().map(|r| &r.run)), ["a", "default"]);
+
+        let records = history.records(&comparator(), &[], runs());
+        assert_eq!(ids(records.iter().map(|r| &r.run)), ["a"]);
+    }
+
+    #[test]
+    fn query_world_records() {
+        let client = MockClient::new();
+        client.add_data(
+            Method::GET,
+            "games/o1y9wo6q/variables",
+            [variable_json(
+                "sub",
+                true,
+                false,
+                json!({ "type": "global" }),
+            )],
+        );
+        client.add_json(
+            Method::GET,
+            "runs?game=o1y9wo6q&category=wkpoo02r&status=verified&orderby=date&direction=asc&offset=0&max=200",
+            http::StatusCode::OK,
+            page(vec![
+                run_json("first", Some(70.0), Some("2020-01-01"), json!({})),
+                run_json("second", Some(65.0), Some("2020-02-01"), json!({})),
+                run_json(
+                    "other",
+                    Some(60.0),
+                    Some("2020-03-01"),
+                    json!({ "values": { "sub": "b" } }),
+                ),
+            ]),
+        );
+
+        let history = WorldRecordHistory::builder()
+            .game("o1y9wo6q")
+            .category("wkpoo02r")
+            .comparator(comparator())
+            .variable("sub", "a")
+            .build()
+            .unwrap();
+        let records = history.query(&client).unwrap();
+        assert_eq!(ids(records.iter().map(|r| &r.run)), ["first", "second"]);
+        assert_eq!(client.requests().len(), 2);
+    }
+
+    #[test]
+    fn query_world_records_with_leaderboard_timing() {
+        let client = MockClient::new();
+        client.add_data(Method::GET, "games/o1y9wo6q", game_json("o1y9wo6q"));
+        client.add_data(
+            Method::GET,
+            "leaderboards/o1y9wo6q/category/wkpoo02r?top=1",
+            json!({
+                "weblink": "https://www.speedrun.com/o1y9wo6q",
+                "game": "o1y9wo6q",
+                "category": "wkpoo02r",
+                "timing": "ingame",
+                "video-only": false,
+                "runs": [],
+                "links": [],
+            }),
+        );
+        client.add_json(
+            Method::GET,
+            "runs?game=o1y9wo6q&category=wkpoo02r&status=verified&orderby=date&direction=asc&offset=0&max=200",
+            http::StatusCode::OK,
+            page(vec![
+                run_json(
+                    "first",
+                    None,
+                    Some("2020-01-01"),
+                    json!({ "times": times_json(Some(70.0), Some(60.0)) }),
+                ),
+                run_json(
+                    "faster_realtime",
+                    None,
+                    Some("2020-02-01"),
+                    json!({ "times": times_json(Some(65.0), Some(62.0)) }),
+                ),
+            ]),
+        );
+
+        // The game ranks runs by real time, but the category overrides it.
+        let history = WorldRecordHistory::builder()
+            .game("o1y9wo6q")
+            .category("wkpoo02r")
+            .build()
+            .unwrap();
+        let records = history.query(&client).unwrap();
+        assert_eq!(ids(records.iter().map(|r| &r.run)), ["first"]);
+        assert_eq!(records[0].time, RunTime::from_millis(60_000));
+        assert_eq!(client.requests().len(), 3);
+    }
+
+    #[test]
+    fn personal_best_progressions() {
+        let variables = [variable("sub", true, false, json!({ "type": "global" }))];
+        let runs = vec![
+            run("first", Some(70.0), Some("2020-01-01")),
+            run("slower", Some(75.0), Some("2020-02-01")),
+            run("pb", Some(65.0), Some("2020-03-01")),
+            run_with(
+                "b",
+                Some(80.0),
+                Some("2020-01-15"),
+                json!({ "values": { "sub": "b" } }),
+            ),
+            run("undated", Some(50.0), None),
+        ];
+        let progressions = PersonalBestHistory::progressions(&comparator(), &variables, runs);
+        assert_eq!(progressions.len(), 2);
+
+        let default = &progressions[0];
+        assert_eq!(default.values[&VariableId::new("sub")], "a");
+        let bests = &default.personal_bests;
+        assert_eq!(ids(bests.iter().map(|pb| &pb.run)), ["first", "pb"]);
+        assert_eq!(bests[0].superseded_by, Some(RunId::new("pb")));
+        assert_eq!(bests[1].previous_run, Some(RunId::new("first")));
+        assert_eq!(bests[1].improvement, Some(RunTime::from_millis(5000)));
+        assert_eq!(default.current().unwrap().run.id, RunId::new("pb"));
+        assert_eq!(default.time_saved(), RunTime::from_millis(5000));
+
+        let other = &progressions[1];
+        assert_eq!(other.values[&VariableId::new("sub")], "b");
+        assert_eq!(other.time_saved(), RunTime::ZERO);
+    }
+
+    #[tokio::test]
+    async fn query_personal_bests_with_placements() {
+        let client = MockClient::new();
+        client.add_data(Method::GET, "games/o1y9wo6q", game_json("o1y9wo6q"));
+        client.add_data(Method::GET, "games/o1y9wo6q/variables", Vec::<()>::new());
+        client.add_json(
+            Method::GET,
+            "runs?user=me&status=verified&orderby=date&direction=asc&offset=0&max=200",
+            http::StatusCode::OK,
+            page(vec![
+                run_json("first", Some(70.0), Some("2020-01-01"), json!({})),
+                run_json("pb", Some(65.0), Some("2020-03-01"), json!({})),
+            ]),
+        );
+        for (date, runs) in [
+            (
+                "2020-01-01",
+                vec![run_json("wr", Some(60.0), None, json!({}))],
+            ),
+            (
+                "2020-03-01",
+                vec![
+                    run_json("wr", Some(60.0), None, json!({})),
+                    run_json("pb", Some(65.0), None, json!({})),
+                    run_json("slow", Some(90.0), None, json!({})),
+                ],
+            ),
+        ] {
+            let runs: Vec<_> = runs
+                .into_iter()
+                .enumerate()
+                .map(|(index, run)| json!({ "place": index + 1, "run": run }))
+                .collect();
+            client.add_data(
+                Method::GET,
+                &format!(
+                    "leaderboards/o1y9wo6q/category/wkpoo02r?timing=realtime&date={}",
+                    date
+                ),
+                json!({
+                    "weblink": "https://www.speedrun.com/o1y9wo6q",
+                    "game": "o1y9wo6q",
+                    "category": "wkpoo02r",
+                    "video-only": false,
+                    "runs": runs,
+                    "links": [],
+                }),
+            );
+        }
+
+        let history = PersonalBestHistory::builder().user("me").build().unwrap();
+        let progressions = history.query_async(&client).await.unwrap();
+        assert_eq!(progressions.len(), 1);
+        let places: Vec<_> = progressions[0]
+            .personal_bests
+            .iter()
+            .map(|pb| pb.place)
+            .collect();
+        assert_eq!(places, [Some(2), Some(2)]);
+    }
+}
@@ -17,13 +17,29 @@ extern crate derive_builder;
 
 mod auth;
 mod client;
+mod middleware;
+mod rate_limit;
+mod retry;
+#[cfg(test)]
+mod test_utils;
 
 pub mod api;
+pub mod cache;
 pub mod error;
+pub mod history;
+pub mod mock;
+pub mod moderation;
+pub mod ranking;
 pub mod types;
+pub mod validation;
 
 pub use auth::AuthError;
-pub use client::{SpeedrunApiBuilder, SpeedrunApiClient, SpeedrunApiClientAsync};
+pub use client::{SpeedrunApiBuilder, SpeedrunApiClient};
+#[cfg(feature = "async")]
+pub use client::SpeedrunApiClientAsync;
+pub use middleware::{redact_headers, Middleware, RequestInfo, RequestLogger};
+pub use rate_limit::{RateLimitMetrics, RateLimiter, DEFAULT_REQUESTS_PER_MINUTE};
+pub use retry::RetryPolicy;
 
 //TODO:
 //      - Tests
@@ -0,0 +1,359 @@
+//! Inspecting and modifying the requests sent by a client.
+use std::{
+    fmt::{self, Debug},
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use bytes::Bytes;
+use http::{HeaderMap, HeaderValue, Method, Request, Response, Uri};
+use log::{log, Level};
+
+/// Hooks called around every request sent by a client.
+///
+/// Middleware is registered with [`SpeedrunApiBuilder::middleware`], and is
+/// run by the client's [`RestClient::intercept_request`] and
+/// [`RestClient::intercept_response`] hooks. [`Middleware::on_request`] is
+/// called in the order the middleware was registered, and
+/// [`Middleware::on_response`] in reverse order. When requests are retried,
+/// the hooks are called once, around all attempts. Requests are seen before
+/// the client adds the API key header, so the key is never passed to
+/// middleware.
+///
+/// Clients wrapping a client with middleware, like
+/// [`CachedClient`](crate::cache::CachedClient), forward the hooks, so
+/// middleware also sees responses served from a cache.
+/// [`Middleware::on_response`] is only called when a response was received.
+/// It is not called when sending the request failed, e.g. because of a
+/// connection error or timeout; the error is returned from the query
+/// instead.
+///
+/// # Example
+///
+/// ```rust ,no_run
+/// use http::{header, HeaderValue, Request};
+/// use speedrun_api::{Middleware, RequestLogger, SpeedrunApiBuilder};
+///
+/// struct UserAgent(HeaderValue);
+///
+/// impl Middleware for UserAgent {
+///     fn on_request(&self, request: &mut Request<Vec<u8>>) {
+///         request
+///             .headers_mut()
+///             .insert(header::USER_AGENT, self.0.clone());
+///     }
+/// }
+///
+/// # fn main() -> speedrun_api::error::SpeedrunApiResult<()> {
+/// let client = SpeedrunApiBuilder::new()
+///     .middleware(UserAgent(HeaderValue::from_static(
+///         "my-app/1.0 (contact@example.com)",
+///     )))
+///     .middleware(RequestLogger::default())
+///     .build()?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`SpeedrunApiBuilder::middleware`]: crate::SpeedrunApiBuilder::middleware
+/// [`RestClient::intercept_request`]: crate::api::RestClient::intercept_request
+/// [`RestClient::intercept_response`]: crate::api::RestClient::intercept_response
+pub trait Middleware: Send + Sync {
+    /// Called before a request is sent.
+    fn on_request(&self, request: &mut Request<Vec<u8>>) {
+        let _ = request;
+    }
+
+    /// Called after a response was received for `request`. Not called when
+    /// the request could not be sent.
+    fn on_response(&self, request: &RequestInfo, response: &mut Response<Bytes>) {
+        let _ = (request, response);
+    }
+}
+
+/// A request that was sent, as passed to [`Middleware::on_response`].
+#[derive(Debug, Clone)]
+pub struct RequestInfo {
+    method: Method,
+    uri: Uri,
+    headers: HeaderMap,
+    started: Instant,
+}
+
+impl RequestInfo {
+    pub(crate) fn new(request: &Request<Vec<u8>>) -> Self {
+        Self {
+            method: request.method().clone(),
+            uri: request.uri().clone(),
+            headers: redact_headers(request.headers()),
+            started: Instant::now(),
+        }
+    }
+
+    /// The method of the request.
+    pub fn method(&self) -> &Method {
+        &self.method
+    }
+
+    /// The URI of the request.
+    pub fn uri(&self) -> &Uri {
+        &self.uri
+    }
+
+    /// The headers of the request, with sensitive values like the API key
+    /// redacted.
+    pub fn headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+
+    /// Time since the request was sent.
+    pub fn elapsed(&self) -> Duration {
+        self.started.elapsed()
+    }
+}
+
+/// Copy `headers`, replacing sensitive values like the API key so they can be
+/// logged.
+pub fn redact_headers(headers: &HeaderMap) -> HeaderMap {
+    let mut redacted = headers.clone();
+    for value in redacted.values_mut() {
+        if value.is_sensitive() {
+            *value = HeaderValue::from_static("<redacted>");
+        }
+    }
+    redacted
+}
+
+/// Logs every request with its response status and how long it took.
+#[derive(Debug, Clone)]
+pub struct RequestLogger {
+    level: Level,
+    headers: bool,
+}
+
+impl RequestLogger {
+    /// Create a logger logging at `level`.
+    pub fn new(level: Level) -> Self {
+        Self {
+            level,
+            headers: false,
+        }
+    }
+
+    /// Also log the (redacted) request headers.
+    pub fn headers(mut self, value: bool) -> Self {
+        self.headers = value;
+        self
+    }
+}
+
+impl Default for RequestLogger {
+    /// Log at [`Level::Debug`].
+    fn default() -> Self {
+        Self::new(Level::Debug)
+    }
+}
+
+impl Middleware for RequestLogger {
+    fn on_response(&self, request: &RequestInfo, response: &mut Response<Bytes>) {
+        log!(
+            self.level,
+            "{} {} -> {} in {:?}",
+            request.method(),
+            request.uri(),
+            response.status(),
+            request.elapsed()
+        );
+        if self.headers {
+            log!(self.level, "request headers: {:?}", request.headers());
+        }
+    }
+}
+
+/// The middleware registered with a client.
+#[derive(Clone, Default)]
+pub(crate) struct MiddlewareChain(Vec<Arc<dyn Middleware>>);
+
+impl MiddlewareChain {
+    pub(crate) fn push<M>(&mut self, middleware: M)
+    where
+        M: Middleware + 'static,
+    {
+        self.0.push(Arc::new(middleware));
+    }
+
+    pub(crate) fn on_request(&self, request: &mut Request<Vec<u8>>) {
+        for middleware in &self.0 {
+            middleware.on_request(request);
+        }
+    }
+
+    pub(crate) fn on_response(&self, request: &RequestInfo, response: &mut Response<Bytes>) {
+        for middleware in self.0.iter().rev() {
+            middleware.on_response(request, response);
+        }
+    }
+}
+
+impl Debug for MiddlewareChain {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} middleware", self.0.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::{Arc, Mutex},
+        time::Duration,
+    };
+
+    use bytes::Bytes;
+    use http::{
+        header, request::Builder as RequestBuilder, HeaderValue, Method, Request, Response,
+    };
+    use url::Url;
+
+    use super::{redact_headers, Middleware, MiddlewareChain, RequestInfo};
+    use crate::{
+        api::{games::Game, ApiError, Client, Query, RestClient},
+        cache::CachedClient,
+        mock::{MockClient, MockError},
+        test_utils::game_json,
+        types,
+    };
+
+    /// Runs middleware the same way as the clients of the crate.
+    struct Chained {
+        inner: MockClient,
+        middleware: MiddlewareChain,
+    }
+
+    impl RestClient for Chained {
+        type Error = MockError;
+
+        fn rest_endpoint(&self, endpoint: &str) -> Result<Url, ApiError<MockError>> {
+            self.inner.rest_endpoint(endpoint)
+        }
+
+        fn has_api_key(&self) -> bool {
+            self.inner.has_api_key()
+        }
+
+        fn intercept_request(
+            &self,
+            request: &mut Request<Vec<u8>>,
+        ) -> Result<(), ApiError<MockError>> {
+            self.middleware.on_request(request);
+            Ok(())
+        }
+
+        fn intercept_response(
+            &self,
+            request: &RequestInfo,
+            response: &mut Response<Bytes>,
+        ) -> Result<(), ApiError<MockError>> {
+            self.middleware.on_response(request, response);
+            Ok(())
+        }
+    }
+
+    impl Client for Chained {
+        fn rest(
+            &self,
+            request: RequestBuilder,
+            body: Vec<u8>,
+        ) -> Result<Response<Bytes>, ApiError<MockError>> {
+            self.inner.rest(request, body)
+        }
+    }
+
+    /// Records the hooks it is called for.
+    struct Record(&'static str, Arc<Mutex<Vec<String>>>);
+
+    impl Middleware for Record {
+        fn on_request(&self, request: &mut Request<Vec<u8>>) {
+            request
+                .headers_mut()
+                .append("x-middleware", HeaderValue::from_static(self.0));
+            self.1.lock().unwrap().push(format!("{} request", self.0));
+        }
+
+        fn on_response(&self, request: &RequestInfo, response: &mut Response<Bytes>) {
+            self.1.lock().unwrap().push(format!(
+                "{} response {} {}",
+                self.0,
+                request.method(),
+                response.status().as_u16()
+            ));
+        }
+    }
+
+    fn chained(calls: &Arc<Mutex<Vec<String>>>) -> Chained {
+        let mut middleware = MiddlewareChain::default();
+        middleware.push(Record("first", calls.clone()));
+        middleware.push(Record("second", calls.clone()));
+        let inner = MockClient::new();
+        inner.add_data(Method::GET, "games/o1y9wo6q", game_json("o1y9wo6q"));
+        Chained { inner, middleware }
+    }
+
+    fn query<C>(client: &C) -> types::Game<'static>
+    where
+        C: Client,
+    {
+        Game::builder()
+            .id("o1y9wo6q")
+            .build()
+            .unwrap()
+            .query(client)
+            .unwrap()
+    }
+
+    #[test]
+    fn middleware_order() {
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let client = chained(&calls);
+        query(&client);
+
+        assert_eq!(
+            *calls.lock().unwrap(),
+            [
+                "first request",
+                "second request",
+                "second response GET 200",
+                "first response GET 200",
+            ]
+        );
+        let requests = client.inner.requests();
+        let seen: Vec<_> = requests[0].headers.get_all("x-middleware").iter().collect();
+        assert_eq!(seen, ["first", "second"]);
+    }
+
+    #[test]
+    fn cache_hits_reach_middleware() {
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let client = CachedClient::new(chained(&calls)).default_ttl(Duration::from_secs(60));
+        query(&client);
+        query(&client);
+
+        assert_eq!(client.inner().inner.requests().len(), 1);
+        assert_eq!(calls.lock().unwrap().len(), 8);
+    }
+
+    #[test]
+    fn redacts_sensitive_headers() {
+        let mut key = HeaderValue::from_static("secret");
+        key.set_sensitive(true);
+        let request = Request::builder()
+            .header("x-api-key", key)
+            .header(header::ACCEPT, "application/json")
+            .body(Vec::new())
+            .unwrap();
+
+        let headers = redact_headers(request.headers());
+        assert_eq!(headers["x-api-key"], "<redacted>");
+        assert_eq!(headers[header::ACCEPT], "application/json");
+        assert_eq!(RequestInfo::new(&request).headers(), &headers);
+    }
+}
@@ -0,0 +1,704 @@
+//! Offline clients for testing code built on this crate.
+//!
+//! [`MockClient`] answers requests with canned responses, matched on the
+//! method, path and query of the request. [`RecordingClient`] wraps a real
+//! client and saves the responses it receives as fixture files, which can later
+//! be replayed with [`RecordingClient`] or loaded into a [`MockClient`].
+//!
+//! # Example
+//!
+//! ```rust
+//! use http::Method;
+//! use serde_json::json;
+//! use speedrun_api::{
+//!     api::{games::Game, Query},
+//!     mock::MockClient,
+//! };
+//!
+//! let client = MockClient::new();
+//! client.add_data(
+//!     Method::GET,
+//!     "games/v1pxjz68",
+//!     json!({ "id": "v1pxjz68", "abbreviation": "sms" }),
+//! );
+//!
+//! let endpoint = Game::builder().id("v1pxjz68").build().unwrap();
+//! let game: serde_json::Value = endpoint.query(&client).unwrap();
+//! assert_eq!(game["abbreviation"], "sms");
+//! assert_eq!(client.requests().len(), 1);
+//! ```
+use std::{
+    collections::HashMap,
+    fs, io,
+    path::{Path, PathBuf},
+    sync::{Mutex, MutexGuard},
+};
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use http::{
+    header, request::Builder as RequestBuilder, HeaderMap, HeaderValue, Method, Request, Response,
+    StatusCode,
+};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use url::Url;
+
+use crate::{
+    api::{self, ApiError, AsyncClient, Client, RestClient},
+    client::SPEEDRUN_API_BASE_URL,
+    middleware::RequestInfo,
+};
+
+/// Errors from the [`MockClient`] and [`RecordingClient`].
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum MockError {
+    /// No response was registered for the request.
+    #[error("no response registered for {method} {url}")]
+    NoResponse {
+        /// Method of the request.
+        method: Method,
+        /// URL of the request.
+        url: String,
+    },
+    /// The request could not be built.
+    #[error("HTTP error: {0}")]
+    Http(#[from] http::Error),
+    /// The request URL could not be parsed.
+    #[error("url parse error: {0}")]
+    Parse(#[from] url::ParseError),
+    /// Reading or writing a fixture file failed.
+    #[error("fixture I/O error: {0}")]
+    Io(#[from] io::Error),
+    /// A fixture file could not be parsed or written.
+    #[error("fixture format error: {0}")]
+    Fixture(#[from] serde_json::Error),
+}
+
+/// Errors from the [`RecordingClient`].
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum RecordingError<E>
+where
+    E: std::error::Error + Send + Sync + 'static,
+{
+    /// Error from the wrapped client.
+    #[error(transparent)]
+    Inner(E),
+    /// Error handling fixtures.
+    #[error(transparent)]
+    Fixture(#[from] MockError),
+}
+
+/// A request received by a [`MockClient`].
+#[derive(Debug, Clone)]
+pub struct MockRequest {
+    /// Method of the request.
+    pub method: Method,
+    /// Full URL of the request.
+    pub url: String,
+    /// Headers of the request.
+    pub headers: HeaderMap,
+    /// Body of the request.
+    pub body: Vec<u8>,
+}
+
+/// A client answering requests with canned responses.
+///
+/// Responses are matched on the request method, the path relative to the API
+/// base URL and the query parameters (in any order). Registered responses can
+/// be returned any number of times.
+#[derive(Debug)]
+pub struct MockClient {
+    base: Url,
+    api_key: bool,
+    responses: Mutex<HashMap<MockKey, MockResponse>>,
+    requests: Mutex<Vec<MockRequest>>,
+}
+
+/// Mode of operation for a [`RecordingClient`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecordMode {
+    /// Send every request to the wrapped client and save the responses.
+    Record,
+    /// Answer requests from saved fixtures only, never using the wrapped
+    /// client.
+    Replay,
+    /// Answer requests from saved fixtures, and record the ones that are
+    /// missing.
+    ReplayOrRecord,
+}
+
+/// A client wrapper saving responses as fixture files and replaying them.
+#[derive(Debug)]
+pub struct RecordingClient<C> {
+    inner: C,
+    dir: PathBuf,
+    mode: RecordMode,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct MockKey {
+    method: Method,
+    path: String,
+    query: Vec<(String, String)>,
+}
+
+#[derive(Debug, Clone)]
+struct MockResponse {
+    status: StatusCode,
+    headers: HeaderMap,
+    body: Bytes,
+}
+
+/// On-disk format of a recorded response.
+#[derive(Debug, Serialize, Deserialize)]
+struct Fixture {
+    method: String,
+    url: String,
+    status: u16,
+    #[serde(default)]
+    headers: Vec<(String, String)>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    json: Option<serde_json::Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    text: Option<String>,
+}
+
+impl MockKey {
+    fn new(method: Method, base: &Url, url: &Url) -> Self {
+        let path = url
+            .path()
+            .strip_prefix(base.path())
+            .unwrap_or_else(|| url.path())
+            .trim_matches('/')
+            .to_owned();
+        let mut query: Vec<_> = url.query_pairs().into_owned().collect();
+        query.sort();
+        Self {
+            method,
+            path,
+            query,
+        }
+    }
+}
+
+impl MockResponse {
+    fn to_response(&self) -> Result<Response<Bytes>, http::Error> {
+        let mut rsp = Response::builder().status(self.status);
+        if let Some(headers) = rsp.headers_mut() {
+            headers.extend(self.headers.clone());
+        }
+        rsp.body(self.body.clone())
+    }
+}
+
+impl Fixture {
+    fn from_response(method: &Method, url: &str, rsp: &Response<Bytes>) -> Self {
+        let (json, text) = match serde_json::from_slice(rsp.body()) {
+            Ok(value) => (Some(value), None),
+            Err(_) => (None, Some(String::from_utf8_lossy(rsp.body()).into_owned())),
+        };
+        Self {
+            method: method.to_string(),
+            url: url.into(),
+            status: rsp.status().as_u16(),
+            headers: rsp
+                .headers()
+                .iter()
+                .filter_map(|(name, value)| {
+                    Some((name.as_str().into(), value.to_str().ok()?.into()))
+                })
+                .collect(),
+            json,
+            text,
+        }
+    }
+
+    fn load(path: &Path) -> Result<Self, MockError> {
+        Ok(serde_json::from_slice(&fs::read(path)?)?)
+    }
+
+    fn save(&self, path: &Path) -> Result<(), MockError> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, serde_json::to_vec_pretty(self)?)?;
+        Ok(())
+    }
+
+    fn method(&self) -> Result<Method, MockError> {
+        self.method
+            .parse()
+            .map_err(http::Error::from)
+            .map_err(From::from)
+    }
+
+    fn response(&self) -> Result<MockResponse, MockError> {
+        let mut headers = HeaderMap::new();
+        for (name, value) in &self.headers {
+            headers.append(
+                header::HeaderName::from_bytes(name.as_bytes()).map_err(http::Error::from)?,
+                HeaderValue::from_str(value).map_err(http::Error::from)?,
+            );
+        }
+        let body = match (&self.json, &self.text) {
+            (Some(json), _) => serde_json::to_vec(json)?.into(),
+            (None, Some(text)) => text.clone().into(),
+            (None, None) => Bytes::new(),
+        };
+        Ok(MockResponse {
+            status: StatusCode::from_u16(self.status).map_err(http::Error::from)?,
+            headers,
+            body,
+        })
+    }
+}
+
+impl MockClient {
+    /// Create a new mock client, using the speedrun.com API base URL.
+    pub fn new() -> Self {
+        Self::with_base_url(Url::parse(SPEEDRUN_API_BASE_URL).expect("valid base URL"))
+    }
+
+    /// Create a new mock client, using `base` as the API base URL.
+    pub fn with_base_url(base: Url) -> Self {
+        Self {
+            base,
+            api_key: false,
+            responses: Mutex::default(),
+            requests: Mutex::default(),
+        }
+    }
+
+    /// Create a new mock client answering requests with the fixtures saved in
+    /// `dir` by a [`RecordingClient`].
+    pub fn from_fixtures<P>(dir: P) -> Result<Self, MockError>
+    where
+        P: AsRef<Path>,
+    {
+        let client = Self::new();
+        client.load_fixtures(dir)?;
+        Ok(client)
+    }
+
+    /// Pretend an API key was set, so endpoints that require authentication
+    /// can be used.
+    pub fn authenticated(mut self, value: bool) -> Self {
+        self.api_key = value;
+        self
+    }
+
+    /// Register `response` for requests to `path` (relative to the API base
+    /// URL, optionally including a query string) using `method`.
+    ///
+    /// # Panics
+    ///
+    /// If `path` cannot be joined to the base URL.
+    pub fn add(&self, method: Method, path: &str, response: Response<Bytes>) {
+        let url = self
+            .base
+            .join(path.trim_start_matches('/'))
+            .expect("valid mock path");
+        let (parts, body) = response.into_parts();
+        lock(&self.responses).insert(
+            MockKey::new(method, &self.base, &url),
+            MockResponse {
+                status: parts.status,
+                headers: parts.headers,
+                body,
+            },
+        );
+    }
+
+    /// Register a JSON response with the given `status`.
+    pub fn add_json<T>(&self, method: Method, path: &str, status: StatusCode, body: T)
+    where
+        T: Serialize,
+    {
+        let body = serde_json::to_vec(&body).expect("serializable mock response");
+        let response = Response::builder()
+            .status(status)
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(body.into())
+            .expect("valid mock response");
+        self.add(method, path, response);
+    }
+
+    /// Register a successful response, wrapping `data` in the `{"data": ...}`
+    /// envelope used by the API.
+    pub fn add_data<T>(&self, method: Method, path: &str, data: T)
+    where
+        T: Serialize,
+    {
+        self.add_json(
+            method,
+            path,
+            StatusCode::OK,
+            serde_json::json!({ "data": data }),
+        );
+    }
+
+    /// Register the responses of all fixtures saved in `dir`.
+    pub fn load_fixtures<P>(&self, dir: P) -> Result<(), MockError>
+    where
+        P: AsRef<Path>,
+    {
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.extension().is_none_or(|ext| ext != "json") {
+                continue;
+            }
+            let fixture = Fixture::load(&path)?;
+            let url = Url::parse(&fixture.url)?;
+            lock(&self.responses).insert(
+                MockKey::new(fixture.method()?, &self.base, &url),
+                fixture.response()?,
+            );
+        }
+        Ok(())
+    }
+
+    /// All requests received so far.
+    pub fn requests(&self) -> Vec<MockRequest> {
+        lock(&self.requests).clone()
+    }
+
+    fn respond(
+        &self,
+        request: RequestBuilder,
+        body: Vec<u8>,
+    ) -> Result<Response<Bytes>, MockError> {
+        let request = request.body(body)?;
+        let url = Url::parse(&request.uri().to_string())?;
+        let key = MockKey::new(request.method().clone(), &self.base, &url);
+        let (parts, body) = request.into_parts();
+        lock(&self.requests).push(MockRequest {
+            method: parts.method.clone(),
+            url: url.to_string(),
+            headers: parts.headers,
+            body,
+        });
+
+        let response = lock(&self.responses).get(&key).cloned();
+        match response {
+            Some(response) => Ok(response.to_response()?),
+            None => Err(MockError::NoResponse {
+                method: parts.method,
+                url: url.into(),
+            }),
+        }
+    }
+}
+
+impl Default for MockClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RestClient for MockClient {
+    type Error = MockError;
+
+    fn rest_endpoint(&self, endpoint: &str) -> Result<Url, ApiError<Self::Error>> {
+        self.base
+            .join(endpoint.trim_start_matches('/'))
+            .map_err(From::from)
+    }
+
+    fn has_api_key(&self) -> bool {
+        self.api_key
+    }
+}
+
+impl Client for MockClient {
+    fn rest(
+        &self,
+        request: RequestBuilder,
+        body: Vec<u8>,
+    ) -> Result<Response<Bytes>, ApiError<Self::Error>> {
+        self.respond(request, body).map_err(ApiError::client)
+    }
+}
+
+#[async_trait]
+impl AsyncClient for MockClient {
+    async fn rest_async(
+        &self,
+        request: RequestBuilder,
+        body: Vec<u8>,
+    ) -> Result<Response<Bytes>, ApiError<Self::Error>> {
+        self.respond(request, body).map_err(ApiError::client)
+    }
+}
+
+impl<C> RecordingClient<C> {
+    /// Wrap `inner`, keeping fixtures in `dir`.
+    pub fn new<P>(inner: C, dir: P, mode: RecordMode) -> Self
+    where
+        P: Into<PathBuf>,
+    {
+        Self {
+            inner,
+            dir: dir.into(),
+            mode,
+        }
+    }
+
+    /// The wrapped client.
+    pub fn inner(&self) -> &C {
+        &self.inner
+    }
+
+    /// Unwrap the inner client.
+    pub fn into_inner(self) -> C {
+        self.inner
+    }
+
+    /// Path of the fixture file for a request.
+    fn fixture_path(&self, method: &Method, url: &str, body: &[u8]) -> PathBuf {
+        let mut key = format!("{} {}", method, url).into_bytes();
+        key.extend_from_slice(body);
+        let name: String = Url::parse(url)
+            .map(|url| url.path().to_owned())
+            .unwrap_or_default()
+            .trim_matches('/')
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .take(64)
+            .collect();
+        self.dir.join(format!(
+            "{}-{}-{:016x}.json",
+            method.as_str().to_ascii_lowercase(),
+            name,
+            api::fnv1a(&key)
+        ))
+    }
+
+    /// Look up the saved response for a request.
+    fn replay(&self, path: &Path) -> Result<Option<Response<Bytes>>, MockError> {
+        if self.mode == RecordMode::Record {
+            return Ok(None);
+        }
+        match Fixture::load(path) {
+            Ok(fixture) => Ok(Some(fixture.response()?.to_response()?)),
+            Err(MockError::Io(err)) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    fn record(
+        &self,
+        path: &Path,
+        method: &Method,
+        url: &str,
+        rsp: &Response<Bytes>,
+    ) -> Result<(), MockError> {
+        Fixture::from_response(method, url, rsp).save(path)
+    }
+}
+
+impl<C> RestClient for RecordingClient<C>
+where
+    C: RestClient,
+{
+    type Error = RecordingError<C::Error>;
+
+    fn rest_endpoint(&self, endpoint: &str) -> Result<Url, ApiError<Self::Error>> {
+        self.inner
+            .rest_endpoint(endpoint)
+            .map_err(|err| err.map_client(RecordingError::Inner))
+    }
+
+    fn has_api_key(&self) -> bool {
+        self.inner.has_api_key()
+    }
+
+    fn intercept_request(
+        &self,
+        request: &mut Request<Vec<u8>>,
+    ) -> Result<(), ApiError<Self::Error>> {
+        self.inner
+            .intercept_request(request)
+            .map_err(|err| err.map_client(RecordingError::Inner))
+    }
+
+    fn intercept_response(
+        &self,
+        request: &RequestInfo,
+        response: &mut Response<Bytes>,
+    ) -> Result<(), ApiError<Self::Error>> {
+        self.inner
+            .intercept_response(request, response)
+            .map_err(|err| err.map_client(RecordingError::Inner))
+    }
+}
+
+/// Method and URL of a request under construction.
+fn request_target(request: &RequestBuilder) -> (Method, String) {
+    (
+        request.method_ref().cloned().unwrap_or_default(),
+        request
+            .uri_ref()
+            .map(ToString::to_string)
+            .unwrap_or_default(),
+    )
+}
+
+fn missing_fixture<E>(method: Method, url: String) -> ApiError<RecordingError<E>>
+where
+    E: std::error::Error + Send + Sync + 'static,
+{
+    ApiError::client(RecordingError::Fixture(MockError::NoResponse {
+        method,
+        url,
+    }))
+}
+
+impl<C> Client for RecordingClient<C>
+where
+    C: Client,
+{
+    fn rest(
+        &self,
+        request: RequestBuilder,
+        body: Vec<u8>,
+    ) -> Result<Response<Bytes>, ApiError<Self::Error>> {
+        let (method, url) = request_target(&request);
+        let path = self.fixture_path(&method, &url, &body);
+        if let Some(rsp) = self.replay(&path).map_err(fixture_error)? {
+            return Ok(rsp);
+        }
+        if self.mode == RecordMode::Replay {
+            return Err(missing_fixture(method, url));
+        }
+        let rsp = self
+            .inner
+            .rest(request, body)
+            .map_err(|err| err.map_client(RecordingError::Inner))?;
+        self.record(&path, &method, &url, &rsp)
+            .map_err(fixture_error)?;
+        Ok(rsp)
+    }
+}
+
+#[async_trait]
+impl<C> AsyncClient for RecordingClient<C>
+where
+    C: AsyncClient + Sync,
+{
+    async fn rest_async(
+        &self,
+        request: RequestBuilder,
+        body: Vec<u8>,
+    ) -> Result<Response<Bytes>, ApiError<Self::Error>> {
+        let (method, url) = request_target(&request);
+        let path = self.fixture_path(&method, &url, &body);
+        if let Some(rsp) = self.replay(&path).map_err(fixture_error)? {
+            return Ok(rsp);
+        }
+        if self.mode == RecordMode::Replay {
+            return Err(missing_fixture(method, url));
+        }
+        let rsp = self
+            .inner
+            .rest_async(request, body)
+            .await
+            .map_err(|err| err.map_client(RecordingError::Inner))?;
+        self.record(&path, &method, &url, &rsp)
+            .map_err(fixture_error)?;
+        Ok(rsp)
+    }
+}
+
+fn fixture_error<E>(err: MockError) -> ApiError<RecordingError<E>>
+where
+    E: std::error::Error + Send + Sync + 'static,
+{
+    ApiError::client(RecordingError::Fixture(err))
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|err| err.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::api::{games::Game, Query};
+
+    fn game() -> Game<'static> {
+        Game::builder().id("v1pxjz68").build().unwrap()
+    }
+
+    /// A fresh directory for fixtures.
+    fn fixture_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("speedrun-api-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn query_parameters_match_in_any_order() {
+        let client = MockClient::new();
+        client.add_data(Method::GET, "runs?status=new&game=abc", json!([]));
+        let url = client.rest_endpoint("runs?game=abc&status=new").unwrap();
+        let request = http::Request::builder().uri(url.as_str());
+        let rsp = client.rest(request, Vec::new()).unwrap();
+        assert_eq!(rsp.status(), StatusCode::OK);
+    }
+
+    #[test]
+    fn unknown_requests_fail() {
+        let client = MockClient::new();
+        let result: Result<serde_json::Value, _> = game().query(&client);
+        match result {
+            Err(ApiError::Client(MockError::NoResponse { method, url })) => {
+                assert_eq!(method, Method::GET);
+                assert!(url.ends_with("/games/v1pxjz68"));
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+        assert_eq!(client.requests().len(), 1);
+    }
+
+    #[test]
+    fn recorded_fixtures_are_replayed() {
+        let dir = fixture_dir("replay");
+        let inner = MockClient::new();
+        inner.add_data(Method::GET, "games/v1pxjz68", json!({ "id": "v1pxjz68" }));
+
+        let recording = RecordingClient::new(inner, &dir, RecordMode::Record);
+        let _: serde_json::Value = game().query(&recording).unwrap();
+        assert_eq!(recording.inner().requests().len(), 1);
+
+        let replaying = RecordingClient::new(recording.into_inner(), &dir, RecordMode::Replay);
+        let game_data: serde_json::Value = game().query(&replaying).unwrap();
+        assert_eq!(game_data["id"], "v1pxjz68");
+        assert_eq!(replaying.inner().requests().len(), 1);
+
+        let mock = MockClient::from_fixtures(&dir).unwrap();
+        let game_data: serde_json::Value = game().query(&mock).unwrap();
+        assert_eq!(game_data["id"], "v1pxjz68");
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn replay_without_fixture_fails() {
+        let dir = fixture_dir("missing");
+        let client = RecordingClient::new(MockClient::new(), &dir, RecordMode::Replay);
+        let result: Result<serde_json::Value, _> = game().query(&client);
+        assert!(matches!(
+            result,
+            Err(ApiError::Client(RecordingError::Fixture(
+                MockError::NoResponse { .. }
+            )))
+        ));
+        assert!(client.inner().requests().is_empty());
+    }
+}
@@ -0,0 +1,427 @@
+//! Moderation helpers.
+//!
+//! [`VerificationQueue`] lists the runs awaiting verification in the games
+//! moderated by the authenticated user, and applies verification decisions
+//! in batches.
+//!
+//! # Example
+//!
+//! ```rust ,no_run
+//! use speedrun_api::{
+//!     moderation::{Decision, VerificationQueue},
+//!     SpeedrunApiBuilder,
+//! };
+//!
+//! # fn main() -> speedrun_api::error::SpeedrunApiResult<()> {
+//! let client = SpeedrunApiBuilder::new().api_key("my api key").build()?;
+//! let queue = VerificationQueue::load(&client)?.dry_run(true);
+//!
+//! let decisions = queue
+//!     .pending(&client)?
+//!     .into_iter()
+//!     .filter(|run| run.videos.is_some())
+//!     .map(|run| Decision::verify(run.id));
+//! for outcome in queue.apply(&client, decisions) {
+//!     println!("{}", outcome);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+use std::fmt::{self, Display};
+
+use futures::TryStreamExt;
+
+use crate::{
+    api::{
+        games::{BulkGames, GameId},
+        profile::Profile,
+        runs::{NewStatus, RunId, RunStatus, Runs, RunsSorting, UpdateRunStatus},
+        ApiError, AsyncClient, AsyncQuery, Client, Direction, PageOptions, PagedEndpointExt, Query,
+    },
+    types::{BulkGame, Run, User},
+};
+
+/// A verification decision for a run.
+#[derive(Debug, Clone)]
+pub struct Decision<'a> {
+    /// The run to update.
+    pub run: RunId<'a>,
+    /// The new status of the run.
+    pub status: NewStatus,
+}
+
+impl<'a> Decision<'a> {
+    /// Verify a run.
+    pub fn verify<R>(run: R) -> Self
+    where
+        R: Into<RunId<'a>>,
+    {
+        Self {
+            run: run.into(),
+            status: NewStatus::Verified,
+        }
+    }
+
+    /// Reject a run with the given reason.
+    pub fn reject<R, S>(run: R, reason: S) -> Self
+    where
+        R: Into<RunId<'a>>,
+        S: Into<String>,
+    {
+        Self {
+            run: run.into(),
+            status: NewStatus::Rejected {
+                reason: reason.into(),
+            },
+        }
+    }
+}
+
+impl Display for Decision<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.status {
+            NewStatus::Verified => write!(f, "verify run {}", self.run),
+            NewStatus::Rejected { reason } => write!(f, "reject run {} ({})", self.run, reason),
+        }
+    }
+}
+
+/// The result of applying a [`Decision`].
+#[derive(Debug)]
+pub struct Outcome<'a, E>
+where
+    E: std::error::Error + Send + Sync + 'static,
+{
+    /// The decision that was applied.
+    pub decision: Decision<'a>,
+    /// The updated run, `None` in dry-run mode.
+    pub result: Result<Option<Run<'static>>, ApiError<E>>,
+}
+
+impl<E> Outcome<'_, E>
+where
+    E: std::error::Error + Send + Sync + 'static,
+{
+    /// Returns `true` if the decision was applied, or would have been applied
+    /// in dry-run mode.
+    pub fn is_ok(&self) -> bool {
+        self.result.is_ok()
+    }
+}
+
+impl<E> Display for Outcome<'_, E>
+where
+    E: std::error::Error + Send + Sync + 'static,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.result {
+            Ok(Some(_)) => write!(f, "{}: done", self.decision),
+            Ok(None) => write!(f, "{}: skipped (dry run)", self.decision),
+            Err(err) => write!(f, "{}: failed: {}", self.decision, err),
+        }
+    }
+}
+
+/// The queue of runs awaiting verification in a set of games.
+///
+/// Changing the status of a run requires an API key for a user moderating the
+/// game. In dry-run mode, decisions are logged but not sent.
+#[derive(Debug, Clone)]
+pub struct VerificationQueue<'a> {
+    games: Vec<GameId<'a>>,
+    dry_run: bool,
+}
+
+impl<'a> VerificationQueue<'a> {
+    /// Create a queue for the given games.
+    pub fn new<I, G>(games: I) -> Self
+    where
+        I: IntoIterator<Item = G>,
+        G: Into<GameId<'a>>,
+    {
+        Self {
+            games: games.into_iter().map(Into::into).collect(),
+            dry_run: false,
+        }
+    }
+
+    /// Only log decisions instead of applying them.
+    pub fn dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// The games in this queue.
+    pub fn games(&self) -> &[GameId<'a>] {
+        &self.games
+    }
+
+    /// Retrieve the pending runs using a synchronous client, oldest
+    /// submission first.
+    pub fn pending<C>(&self, client: &C) -> Result<Vec<Run<'static>>, ApiError<C::Error>>
+    where
+        C: Client,
+    {
+        let mut runs = Vec::new();
+        for game in &self.games {
+            let endpoint = pending_endpoint(game);
+            for run in endpoint.iter_with(client, PageOptions::largest_pages()) {
+                runs.push(run?);
+            }
+        }
+        sort_oldest_first(&mut runs);
+        Ok(runs)
+    }
+
+    /// Retrieve the pending runs using an asynchronous client, oldest
+    /// submission first.
+    pub async fn pending_async<C>(
+        &self,
+        client: &C,
+    ) -> Result<Vec<Run<'static>>, ApiError<C::Error>>
+    where
+        C: AsyncClient + Sync,
+    {
+        let mut runs = Vec::new();
+        for game in &self.games {
+            let endpoint = pending_endpoint(game);
+            let page: Vec<Run<'static>> = endpoint
+                .stream_with(client, PageOptions::largest_pages())
+                .try_collect()
+                .await?;
+            runs.extend(page);
+        }
+        sort_oldest_first(&mut runs);
+        Ok(runs)
+    }
+
+    /// Apply decisions using a synchronous client.
+    ///
+    /// Every decision is applied, even if an earlier one failed.
+    pub fn apply<'d, C, I>(&self, client: &C, decisions: I) -> Vec<Outcome<'d, C::Error>>
+    where
+        C: Client,
+        I: IntoIterator<Item = Decision<'d>>,
+    {
+        decisions
+            .into_iter()
+            .map(|decision| {
+                let result = match self.update_endpoint(&decision) {
+                    Some(endpoint) => endpoint.query(client).map(Some),
+                    None => Ok(None),
+                };
+                Outcome { decision, result }
+            })
+            .collect()
+    }
+
+    /// Apply decisions using an asynchronous client.
+    ///
+    /// Every decision is applied, even if an earlier one failed.
+    pub async fn apply_async<'d, C, I>(
+        &self,
+        client: &C,
+        decisions: I,
+    ) -> Vec<Outcome<'d, C::Error>>
+    where
+        C: AsyncClient + Sync,
+        I: IntoIterator<Item = Decision<'d>>,
+    {
+        let mut outcomes = Vec::new();
+        for decision in decisions {
+            let result = match self.update_endpoint(&decision) {
+                Some(endpoint) => endpoint.query_async(client).await.map(Some),
+                None => Ok(None),
+            };
+            outcomes.push(Outcome { decision, result });
+        }
+        outcomes
+    }
+
+    /// The request applying `decision`, or `None` in dry-run mode.
+    fn update_endpoint<'d>(&self, decision: &Decision<'d>) -> Option<UpdateRunStatus<'d>> {
+        if self.dry_run {
+            log::info!("dry run: {}", decision);
+            return None;
+        }
+        let endpoint = UpdateRunStatus::builder()
+            .id(decision.run.clone())
+            .status(decision.status.clone())
+            .build()
+            .expect("all required fields are set");
+        Some(endpoint)
+    }
+}
+
+impl VerificationQueue<'static> {
+    /// Create a queue for all games moderated by the authenticated user,
+    /// using a synchronous client.
+    pub fn load<C>(client: &C) -> Result<Self, ApiError<C::Error>>
+    where
+        C: Client,
+    {
+        let user: User<'static> = Profile::builder()
+            .build()
+            .expect("all required fields are set")
+            .query(client)?;
+        let endpoint = moderated_endpoint(&user);
+        let games = endpoint
+            .iter(client)
+            .map(|game: Result<BulkGame<'static>, _>| game.map(|game| game.id))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self::new(games))
+    }
+
+    /// Create a queue for all games moderated by the authenticated user,
+    /// using an asynchronous client.
+    pub async fn load_async<C>(client: &C) -> Result<Self, ApiError<C::Error>>
+    where
+        C: AsyncClient + Sync,
+    {
+        let user: User<'static> = Profile::builder()
+            .build()
+            .expect("all required fields are set")
+            .query_async(client)
+            .await?;
+        let endpoint = moderated_endpoint(&user);
+        let games: Vec<BulkGame<'static>> = endpoint.stream(client).try_collect().await?;
+        Ok(Self::new(games.into_iter().map(|game| game.id)))
+    }
+}
+
+fn moderated_endpoint<'a>(user: &User<'a>) -> BulkGames<'a> {
+    BulkGames::builder()
+        .moderator(user.id.clone())
+        .build()
+        .expect("all required fields are set")
+}
+
+fn pending_endpoint<'a>(game: &GameId<'a>) -> Runs<'a> {
+    Runs::builder()
+        .game(game.clone())
+        .status(RunStatus::New)
+        .orderby(RunsSorting::Submitted)
+        .direction(Direction::Asc)
+        .build()
+        .expect("all required fields are set")
+}
+
+/// Runs without a submission date are sorted last.
+fn sort_oldest_first(runs: &mut [Run<'_>]) {
+    runs.sort_by(|a, b| match (a.submitted, b.submitted) {
+        (Some(a), Some(b)) => a.cmp(&b),
+        (Some(_), None) => std::cmp::Ordering::Less,
+        (None, Some(_)) => std::cmp::Ordering::Greater,
+        (None, None) => a.date.cmp(&b.date),
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use http::{Method, StatusCode};
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        mock::MockClient,
+        test_utils::{page, run_json},
+    };
+
+    fn decisions() -> Vec<Decision<'static>> {
+        vec![
+            Decision::verify("good"),
+            Decision::reject("bad", "no video"),
+        ]
+    }
+
+    #[test]
+    fn dry_run_sends_nothing() {
+        let client = MockClient::new().authenticated(true);
+        let queue = VerificationQueue::new(vec!["o1y9wo6q"]).dry_run(true);
+        let outcomes = queue.apply(&client, decisions());
+        assert!(outcomes
+            .iter()
+            .all(|outcome| matches!(outcome.result, Ok(None))));
+        assert_eq!(
+            outcomes[1].to_string(),
+            "reject run bad (no video): skipped (dry run)"
+        );
+        assert!(client.requests().is_empty());
+    }
+
+    #[test]
+    fn failed_decisions_do_not_stop_the_batch() {
+        let client = MockClient::new().authenticated(true);
+        client.add_data(
+            Method::PUT,
+            "runs/bad/status",
+            run_json("bad", Some(60.0), None, json!({})),
+        );
+        let queue = VerificationQueue::new(vec!["o1y9wo6q"]);
+        let outcomes = queue.apply(&client, decisions());
+
+        assert!(!outcomes[0].is_ok());
+        assert!(outcomes[0]
+            .to_string()
+            .starts_with("verify run good: failed"));
+        assert!(matches!(outcomes[1].result, Ok(Some(_))));
+        assert_eq!(outcomes[1].to_string(), "reject run bad (no video): done");
+
+        let requests = client.requests();
+        assert_eq!(requests.len(), 2);
+        let body: serde_json::Value = serde_json::from_slice(&requests[1].body).unwrap();
+        assert_eq!(body["status"]["status"], "rejected");
+        assert_eq!(body["status"]["reason"], "no video");
+    }
+
+    #[tokio::test]
+    async fn apply_async_matches_apply() {
+        let client = MockClient::new().authenticated(true);
+        client.add_data(
+            Method::PUT,
+            "runs/good/status",
+            run_json("good", Some(60.0), None, json!({})),
+        );
+        let queue = VerificationQueue::new(vec!["o1y9wo6q"]);
+        let outcomes = queue.apply_async(&client, decisions()).await;
+        assert!(matches!(outcomes[0].result, Ok(Some(_))));
+        assert!(!outcomes[1].is_ok());
+
+        let outcomes = queue.dry_run(true).apply_async(&client, decisions()).await;
+        assert!(outcomes
+            .iter()
+            .all(|outcome| matches!(outcome.result, Ok(None))));
+        assert_eq!(client.requests().len(), 2);
+    }
+
+    #[test]
+    fn pending_runs_oldest_first() {
+        let client = MockClient::new();
+        let submitted =
+            |id: &str, at: Option<&str>| run_json(id, Some(60.0), None, json!({ "submitted": at }));
+        client.add_json(
+            Method::GET,
+            "runs?game=a&status=new&orderby=submitted&direction=asc&offset=0&max=200",
+            StatusCode::OK,
+            page(vec![
+                submitted("a-new", Some("2021-03-01T00:00:00Z")),
+                submitted("a-unknown", None),
+            ]),
+        );
+        client.add_json(
+            Method::GET,
+            "runs?game=b&status=new&orderby=submitted&direction=asc&offset=0&max=200",
+            StatusCode::OK,
+            page(vec![submitted("b-old", Some("2021-01-01T00:00:00Z"))]),
+        );
+
+        let queue = VerificationQueue::new(vec!["a", "b"]);
+        let ids: Vec<_> = queue
+            .pending(&client)
+            .unwrap()
+            .into_iter()
+            .map(|run| run.id.to_string())
+            .collect();
+        assert_eq!(ids, ["b-old", "a-new", "a-unknown"]);
+    }
+}
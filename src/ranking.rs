@@ -0,0 +1,570 @@
+//! Ranking runs locally.
+//!
+//! [`RunComparator`] orders runs the way a leaderboard does, using a game's
+//! [`Ruleset`]. This makes it possible to rank runs that were fetched earlier
+//! (or that were not returned by the leaderboard endpoint) without another
+//! request.
+//!
+//! [`LeaderboardRanker`] builds complete leaderboards from a list of runs,
+//! e.g. all runs of a category fetched with the [`Runs`] endpoint. This can be
+//! used for combinations of filters the API does not support, or to see a
+//! leaderboard as it was at an earlier date.
+//!
+//! # Example
+//!
+//! ```rust ,no_run
+//! use speedrun_api::{
+//!     api::{categories::CategoryVariables, games::Game, runs::Runs, PagedEndpointExt, Query},
+//!     ranking::{LeaderboardRanker, RunComparator},
+//!     types, SpeedrunApiBuilder,
+//! };
+//!
+//! # fn main() -> speedrun_api::error::SpeedrunApiResult<()> {
+//! let client = SpeedrunApiBuilder::new().build()?;
+//! let game: types::Game = Game::builder().id("v1pxjz68").build().unwrap().query(&client)?;
+//! let comparator = RunComparator::from_ruleset(&game.ruleset);
+//!
+//! let mut runs = Runs::builder()
+//!     .game("v1pxjz68")
+//!     .category("n2y3r8do")
+//!     .build()
+//!     .unwrap()
+//!     .iter(&client)
+//!     .collect::<Result<Vec<types::Run<'_>>, _>>()?;
+//! runs.sort_by(|a, b| comparator.compare(a, b));
+//!
+//! let variables: Vec<types::Variable<'_>> = CategoryVariables::builder()
+//!     .id("n2y3r8do")
+//!     .build()
+//!     .unwrap()
+//!     .query(&client)?;
+//! for leaderboard in LeaderboardRanker::new(comparator, &variables).rank(runs) {
+//!     println!("{:?}: {} runs", leaderboard.values, leaderboard.runs.len());
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`Runs`]: crate::api::runs::Runs
+use std::{
+    cmp::Ordering,
+    collections::{HashMap, HashSet},
+};
+
+use crate::{
+    api::variables::VariableId,
+    types::{
+        Date, Leaderboard, Player, RankedRun, Ruleset, Run, RunTime, Status, Times, TimingMethod,
+        Variable,
+    },
+};
+
+/// Compares runs the way a leaderboard ranks them.
+///
+/// Runs are ordered by their time in the leaderboard's timing method. When
+/// the game hides milliseconds, times are truncated to whole seconds first, so
+/// runs that only differ in milliseconds tie. Runs that have no time for the
+/// timing method are ranked after all runs that do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RunComparator {
+    timing: TimingMethod,
+    show_milliseconds: bool,
+}
+
+impl RunComparator {
+    /// Create a comparator for the given timing method.
+    pub fn new(timing: TimingMethod, show_milliseconds: bool) -> Self {
+        Self {
+            timing,
+            show_milliseconds,
+        }
+    }
+
+    /// Create a comparator using the default timing method of a game.
+    pub fn from_ruleset(ruleset: &Ruleset) -> Self {
+        Self::new(ruleset.default_time, ruleset.show_milliseconds)
+    }
+
+    /// Create a comparator for a leaderboard of a game.
+    ///
+    /// The leaderboard's timing method is used if it was overridden, otherwise
+    /// the default timing method of the game.
+    pub fn for_leaderboard(ruleset: &Ruleset, leaderboard: &Leaderboard<'_>) -> Self {
+        Self::new(
+            leaderboard.timing.unwrap_or(ruleset.default_time),
+            ruleset.show_milliseconds,
+        )
+    }
+
+    /// The timing method used to rank runs.
+    pub fn timing(&self) -> TimingMethod {
+        self.timing
+    }
+
+    /// Whether milliseconds are taken into account.
+    pub fn show_milliseconds(&self) -> bool {
+        self.show_milliseconds
+    }
+
+    /// The time used to rank a run, truncated to whole seconds if
+    /// milliseconds are hidden.
+    pub fn ranked_time(&self, times: &Times) -> Option<RunTime> {
+        let time = times.get(self.timing)?;
+        if self.show_milliseconds {
+            Some(time)
+        } else {
+            Some(time.truncate_to_secs())
+        }
+    }
+
+    /// Compare two sets of times. Returns [`Ordering::Equal`] for runs that
+    /// tie.
+    pub fn compare_times(&self, a: &Times, b: &Times) -> Ordering {
+        match (self.ranked_time(a), self.ranked_time(b)) {
+            (Some(a), Some(b)) => a.cmp(&b),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => a.primary.cmp(&b.primary),
+        }
+    }
+
+    /// Returns `true` if two runs share the same place.
+    pub fn ties(&self, a: &Run<'_>, b: &Run<'_>) -> bool {
+        self.compare_times(&a.times, &b.times) == Ordering::Equal
+    }
+
+    /// Compare two runs.
+    ///
+    /// Runs that tie are ordered by the date they were played, with the
+    /// earlier run first, as they are listed on the leaderboard.
+    pub fn compare(&self, a: &Run<'_>, b: &Run<'_>) -> Ordering {
+        self.compare_times(&a.times, &b.times)
+            .then_with(|| compare_missing_last(a.date, b.date))
+            .then_with(|| compare_missing_last(a.submitted, b.submitted))
+    }
+}
+
+fn compare_missing_last<T: Ord>(a: Option<T>, b: Option<T>) -> Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) => a.cmp(&b),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
+
+/// A leaderboard built locally by [`LeaderboardRanker`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct LocalLeaderboard<'a> {
+    /// The subcategory values of this leaderboard.
+    pub values: HashMap<VariableId<'a>, String>,
+    /// The ranked runs, fastest first.
+    pub runs: Vec<RankedRun<'a>>,
+}
+
+/// Builds leaderboards from a list of runs.
+///
+/// Runs are split into one leaderboard for each combination of subcategory
+/// values. Runs that do not have a value for a subcategory use the variable's
+/// default value. Only verified runs are ranked, and each player (or group of
+/// players, for runs with several players) keeps only their best run. Runs
+/// with different values for a variable that does not obsolete runs are kept
+/// side by side.
+#[derive(Debug, Clone)]
+pub struct LeaderboardRanker<'a> {
+    comparator: RunComparator,
+    variables: Vec<Variable<'a>>,
+    as_of: Option<Date>,
+}
+
+impl<'a> LeaderboardRanker<'a> {
+    /// Create a new ranker using the variables of the category being ranked.
+    pub fn new(comparator: RunComparator, variables: &[Variable<'a>]) -> Self {
+        Self {
+            comparator,
+            variables: variables.to_vec(),
+            as_of: None,
+        }
+    }
+
+    /// Only rank runs played on or before `date`, to show the leaderboards as
+    /// they were at that point. Runs without a date are ignored.
+    pub fn as_of(mut self, date: Date) -> Self {
+        self.as_of = Some(date);
+        self
+    }
+
+    /// Rank the runs.
+    ///
+    /// Leaderboards are returned ordered by their subcategory values.
+    pub fn rank<I>(&self, runs: I) -> Vec<LocalLeaderboard<'a>>
+    where
+        I: IntoIterator<Item = Run<'a>>,
+    {
+        let mut boards: HashMap<Vec<(VariableId<'a>, String)>, Vec<Run<'a>>> = HashMap::new();
+        for run in runs {
+            if !matches!(run.status, Status::Verified { .. }) {
+                continue;
+            }
+            if let Some(as_of) = self.as_of {
+                if run.date.is_none_or(|date| date > as_of) {
+                    continue;
+                }
+            }
+            boards
+                .entry(subcategory(&self.variables, &run))
+                .or_default()
+                .push(run);
+        }
+
+        let mut boards: Vec<_> = boards
+            .into_iter()
+            .map(|(values, runs)| (self.rank_board(runs), values))
+            .collect();
+        boards.sort_by_cached_key(|(_, values)| {
+            values
+                .iter()
+                .map(|(variable, value)| (variable.to_string(), value.clone()))
+                .collect::<Vec<_>>()
+        });
+        boards
+            .into_iter()
+            .map(|(runs, values)| LocalLeaderboard {
+                values: values.into_iter().collect(),
+                runs,
+            })
+            .collect()
+    }
+
+    /// The values of a run that keep it from being obsoleted by runs with
+    /// other values.
+    fn non_obsoleting(&self, run: &Run<'a>) -> Vec<Option<String>> {
+        self.variables
+            .iter()
+            .filter(|variable| !variable.obsoletes && !variable.is_subcategory)
+            .map(|variable| run.values.get(&variable.id).cloned())
+            .collect()
+    }
+
+    fn rank_board(&self, mut runs: Vec<Run<'a>>) -> Vec<RankedRun<'a>> {
+        runs.sort_by(|a, b| self.comparator.compare(a, b));
+
+        let mut seen = HashSet::new();
+        runs.retain(|run| seen.insert((players(run), self.non_obsoleting(run))));
+
+        let mut ranked: Vec<RankedRun<'a>> = Vec::with_capacity(runs.len());
+        for (index, run) in runs.into_iter().enumerate() {
+            let place = match ranked.last() {
+                Some(previous) if self.comparator.ties(&previous.run, &run) => previous.place,
+                _ => index as i64 + 1,
+            };
+            ranked.push(RankedRun { place, run });
+        }
+        ranked
+    }
+}
+
+/// Identifies the players of a run, independent of their order.
+fn players(run: &Run<'_>) -> Vec<String> {
+    let mut players: Vec<_> = run
+        .players
+        .iter()
+        .map(|player| match player {
+            Player::User { id, .. } => format!("user:{}", id),
+            Player::Guest { name, .. } => format!("guest:{}", name.to_lowercase()),
+        })
+        .collect();
+    players.sort();
+    players
+}
+
+/// The subcategory values of a run, in the order of the variables.
+pub(crate) fn subcategory<'v>(
+    variables: &[Variable<'v>],
+    run: &Run<'_>,
+) -> Vec<(VariableId<'v>, String)> {
+    variables
+        .iter()
+        .filter(|variable| {
+            variable.is_subcategory && variable.applies_to(&run.category, run.level.as_ref())
+        })
+        .filter_map(|variable| {
+            let value = run
+                .values
+                .get(&variable.id)
+                .cloned()
+                .or_else(|| variable.values.default.as_ref().map(ToString::to_string))?;
+            Some((variable.id.clone(), value))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        api::{categories::CategoryId, levels::LevelId},
+        test_utils::{guest, run, run_with, times_json, variable},
+    };
+
+    fn ids(runs: &[Run<'_>]) -> Vec<String> {
+        runs.iter().map(|run| run.id.to_string()).collect()
+    }
+
+    #[test]
+    fn faster_runs_first() {
+        let comparator = RunComparator::new(TimingMethod::Realtime, true);
+        let mut runs = vec![run("slow", Some(62.0), None), run("fast", Some(61.5), None)];
+        runs.sort_by(|a, b| comparator.compare(a, b));
+        assert_eq!(ids(&runs), ["fast", "slow"]);
+    }
+
+    #[test]
+    fn hidden_milliseconds_tie() {
+        let a = run("a", Some(61.2), Some("2021-01-02"));
+        let b = run("b", Some(61.9), Some("2021-01-01"));
+
+        let comparator = RunComparator::new(TimingMethod::Realtime, true);
+        assert!(!comparator.ties(&a, &b));
+        assert_eq!(comparator.compare(&a, &b), Ordering::Less);
+
+        let comparator = RunComparator::new(TimingMethod::Realtime, false);
+        assert!(comparator.ties(&a, &b));
+        // Ties are ordered by the date the run was played.
+        assert_eq!(comparator.compare(&a, &b), Ordering::Greater);
+    }
+
+    #[test]
+    fn ties_without_date_go_last_then_by_submission() {
+        let comparator = RunComparator::new(TimingMethod::Realtime, true);
+        let undated = run("undated", Some(60.0), None);
+        let dated = run("dated", Some(60.0), Some("2021-06-01"));
+        assert_eq!(comparator.compare(&dated, &undated), Ordering::Less);
+
+        let early = run_with(
+            "early",
+            Some(60.0),
+            Some("2021-06-01"),
+            json!({ "submitted": "2021-06-01T10:00:00Z" }),
+        );
+        let late = run_with(
+            "late",
+            Some(60.0),
+            Some("2021-06-01"),
+            json!({ "submitted": "2021-06-02T10:00:00Z" }),
+        );
+        assert_eq!(comparator.compare(&early, &late), Ordering::Less);
+        assert_eq!(comparator.compare(&late, &early), Ordering::Greater);
+    }
+
+    #[test]
+    fn runs_without_time_go_last() {
+        let comparator = RunComparator::new(TimingMethod::Ingame, true);
+        let timed = run_with(
+            "timed",
+            Some(10.0),
+            None,
+            json!({ "times": times_json(Some(10.0), Some(100.0)) }),
+        );
+        let untimed = run("untimed", Some(1.0), None);
+        assert_eq!(comparator.compare(&timed, &untimed), Ordering::Less);
+        assert_eq!(comparator.ranked_time(&untimed.times), None);
+    }
+
+    #[test]
+    fn comparator_from_ruleset_and_leaderboard() {
+        let ruleset: Ruleset = serde_json::from_value(json!({
+            "show-milliseconds": false,
+            "require-verification": true,
+            "require-video": false,
+            "run-times": ["realtime", "ingame"],
+            "default-time": "ingame",
+            "emulators-allowed": false,
+        }))
+        .unwrap();
+        let comparator = RunComparator::from_ruleset(&ruleset);
+        assert_eq!(comparator.timing(), TimingMethod::Ingame);
+        assert!(!comparator.show_milliseconds());
+
+        let leaderboard: Leaderboard<'_> = serde_json::from_value(json!({
+            "weblink": "https://www.speedrun.com/sm64#120_Star",
+            "game": "o1y9wo6q",
+            "category": "wkpoo02r",
+            "video-only": false,
+            "timing": "realtime",
+            "runs": [],
+            "links": [],
+        }))
+        .unwrap();
+        let comparator = RunComparator::for_leaderboard(&ruleset, &leaderboard);
+        assert_eq!(comparator.timing(), TimingMethod::Realtime);
+    }
+
+    fn board_ids(board: &LocalLeaderboard<'_>) -> Vec<(i64, String)> {
+        board
+            .runs
+            .iter()
+            .map(|ranked| (ranked.place, ranked.run.id.to_string()))
+            .collect()
+    }
+
+    fn comparator() -> RunComparator {
+        RunComparator::new(TimingMethod::Realtime, true)
+    }
+
+    #[test]
+    fn ties_share_a_place() {
+        let runs = vec![
+            run("third", Some(62.0), None),
+            run("first", Some(60.0), Some("2021-01-01")),
+            run("second", Some(60.0), Some("2021-01-02")),
+        ];
+        let boards = LeaderboardRanker::new(comparator(), &[]).rank(runs);
+        assert_eq!(boards.len(), 1);
+        assert_eq!(
+            board_ids(&boards[0]),
+            [
+                (1, "first".to_owned()),
+                (1, "second".to_owned()),
+                (3, "third".to_owned())
+            ]
+        );
+    }
+
+    #[test]
+    fn players_keep_their_best_run() {
+        let runs = vec![
+            run_with(
+                "old",
+                Some(70.0),
+                None,
+                json!({ "players": guest("Alice") }),
+            ),
+            run_with("pb", Some(65.0), None, json!({ "players": guest("alice") })),
+            run_with(
+                "other",
+                Some(68.0),
+                None,
+                json!({ "players": guest("Bob") }),
+            ),
+            run_with(
+                "rejected",
+                Some(1.0),
+                None,
+                json!({ "status": { "status": "rejected", "examiner": "x", "reason": "" } }),
+            ),
+            run_with(
+                "new",
+                Some(1.0),
+                None,
+                json!({ "status": { "status": "new" } }),
+            ),
+        ];
+        let boards = LeaderboardRanker::new(comparator(), &[]).rank(runs);
+        assert_eq!(
+            board_ids(&boards[0]),
+            [(1, "pb".to_owned()), (2, "other".to_owned())]
+        );
+    }
+
+    #[test]
+    fn non_obsoleting_values_are_kept_side_by_side() {
+        let variables = [variable("emu", false, false, json!({ "type": "global" }))];
+        let runs = vec![
+            run_with(
+                "console",
+                Some(60.0),
+                None,
+                json!({ "values": { "emu": "a" }, "players": guest("Alice") }),
+            ),
+            run_with(
+                "emu",
+                Some(61.0),
+                None,
+                json!({ "values": { "emu": "b" }, "players": guest("Alice") }),
+            ),
+            run_with(
+                "console-slow",
+                Some(62.0),
+                None,
+                json!({ "values": { "emu": "a" }, "players": guest("Alice") }),
+            ),
+        ];
+        let boards = LeaderboardRanker::new(comparator(), &variables).rank(runs);
+        assert_eq!(
+            board_ids(&boards[0]),
+            [(1, "console".to_owned()), (2, "emu".to_owned())]
+        );
+    }
+
+    #[test]
+    fn subcategories_split_leaderboards() {
+        let variables = [
+            variable("sub", true, false, json!({ "type": "global" })),
+            variable("il-only", true, false, json!({ "type": "all-levels" })),
+        ];
+        let runs = vec![
+            run_with("b", Some(60.0), None, json!({ "values": { "sub": "b" } })),
+            // Runs without a value use the default value.
+            run("default", Some(61.0), None),
+            run_with("a", Some(62.0), None, json!({ "values": { "sub": "a" } })),
+        ];
+        let boards = LeaderboardRanker::new(comparator(), &variables).rank(runs);
+        assert_eq!(boards.len(), 2);
+        assert_eq!(boards[0].values[&VariableId::new("sub")], "a");
+        assert_eq!(boards[0].values.len(), 1, "il-only does not apply");
+        assert_eq!(
+            board_ids(&boards[0]),
+            [(1, "default".to_owned()), (2, "a".to_owned())]
+        );
+        assert_eq!(boards[1].values[&VariableId::new("sub")], "b");
+        assert_eq!(board_ids(&boards[1]), [(1, "b".to_owned())]);
+    }
+
+    #[test]
+    fn as_of_ignores_later_and_undated_runs() {
+        let runs = vec![
+            run("old", Some(70.0), Some("2020-01-01")),
+            run("new", Some(60.0), Some("2021-01-01")),
+            run("undated", Some(50.0), None),
+        ];
+        let date = Date::new(2020, 6, 1).unwrap();
+        let boards = LeaderboardRanker::new(comparator(), &[])
+            .as_of(date)
+            .rank(runs);
+        assert_eq!(board_ids(&boards[0]), [(1, "old".to_owned())]);
+    }
+
+    #[test]
+    fn variable_scopes() {
+        let category = CategoryId::new("wkpoo02r");
+        let level = LevelId::new("level");
+
+        let global = variable("v", false, false, json!({ "type": "global" }));
+        assert!(global.applies_to(&category, None));
+        assert!(global.applies_to(&category, Some(&level)));
+
+        let full_game = variable("v", false, false, json!({ "type": "full-game" }));
+        assert!(full_game.applies_to(&category, None));
+        assert!(!full_game.applies_to(&category, Some(&level)));
+
+        let levels = variable("v", false, false, json!({ "type": "all-levels" }));
+        assert!(!levels.applies_to(&category, None));
+        assert!(levels.applies_to(&category, Some(&level)));
+
+        let single = variable(
+            "v",
+            false,
+            false,
+            json!({ "type": "single-level", "level": "level" }),
+        );
+        assert!(single.applies_to(&category, Some(&level)));
+        assert!(!single.applies_to(&category, Some(&LevelId::new("other"))));
+
+        let mut other_category = global;
+        other_category.category = Some(CategoryId::new("other"));
+        assert!(!other_category.applies_to(&category, None));
+    }
+}
@@ -0,0 +1,261 @@
+//! Client side rate limiting.
+//!
+//! Speedrun.com allows 100 requests per minute. Requests over that budget are
+//! answered with `420` (or `429`) responses. A [`RateLimiter`] keeps requests
+//! within the budget by delaying them before they are sent, and backs off when
+//! the server reports that the limit has been hit anyway.
+use std::{
+    fmt::Debug,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use http::{header, HeaderMap, StatusCode};
+
+/// Number of requests per minute allowed by speedrun.com.
+pub const DEFAULT_REQUESTS_PER_MINUTE: u32 = 100;
+
+const DEFAULT_MAX_RETRIES: u32 = 3;
+
+/// A token bucket rate limiter.
+///
+/// Cloning a `RateLimiter` returns a handle to the same bucket, so every client
+/// built from the same [`SpeedrunApiBuilder`] shares one request budget.
+///
+/// [`SpeedrunApiBuilder`]: crate::SpeedrunApiBuilder
+#[derive(Clone)]
+pub struct RateLimiter {
+    state: Arc<Mutex<State>>,
+    capacity: f64,
+    period: Duration,
+    max_retries: u32,
+}
+
+/// Statistics on how a [`RateLimiter`] has delayed requests.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimitMetrics {
+    /// Number of requests that passed through the limiter.
+    pub requests: u64,
+    /// Number of requests that had to wait before being sent.
+    pub delayed_requests: u64,
+    /// Total time spent waiting, across all requests.
+    pub total_wait: Duration,
+    /// Longest time a single request had to wait.
+    pub max_wait: Duration,
+    /// Number of rate limit responses (`420`/`429`) received from the server.
+    pub rate_limited_responses: u64,
+}
+
+#[derive(Debug)]
+struct State {
+    tokens: f64,
+    last_refill: Instant,
+    metrics: RateLimitMetrics,
+}
+
+impl RateLimiter {
+    /// Create a new rate limiter allowing `requests` requests every `period`.
+    ///
+    /// # Panics
+    ///
+    /// If `requests` or `period` is zero.
+    pub fn new(requests: u32, period: Duration) -> Self {
+        assert!(requests > 0, "rate limit must allow at least one request");
+        assert!(!period.is_zero(), "rate limit period must be non-zero");
+        let capacity = f64::from(requests);
+        Self {
+            state: Arc::new(Mutex::new(State {
+                tokens: capacity,
+                last_refill: Instant::now(),
+                metrics: RateLimitMetrics::default(),
+            })),
+            capacity,
+            period,
+            max_retries: DEFAULT_MAX_RETRIES,
+        }
+    }
+
+    /// Create a new rate limiter allowing `requests` requests per minute.
+    pub fn per_minute(requests: u32) -> Self {
+        Self::new(requests, Duration::from_secs(60))
+    }
+
+    /// Maximum number of times a request is resent after the server responded
+    /// that the rate limit was exceeded (default: 3).
+    ///
+    /// Like other retries, only idempotent requests are resent, unless
+    /// [`RetryPolicy::retry_non_idempotent`](crate::RetryPolicy::retry_non_idempotent)
+    /// is enabled.
+    pub fn max_retries(mut self, value: u32) -> Self {
+        self.max_retries = value;
+        self
+    }
+
+    /// Returns a snapshot of the metrics collected so far.
+    pub fn metrics(&self) -> RateLimitMetrics {
+        self.lock().metrics
+    }
+
+    pub(crate) fn retries(&self) -> u32 {
+        self.max_retries
+    }
+
+    /// Take a token from the bucket, returning how long the caller must wait
+    /// before sending its request.
+    pub(crate) fn reserve(&self) -> Duration {
+        let rate = self.rate();
+        let mut state = self.lock();
+        let now = Instant::now();
+        if now > state.last_refill {
+            let elapsed = now.duration_since(state.last_refill).as_secs_f64();
+            state.tokens = (state.tokens + elapsed * rate).min(self.capacity);
+            state.last_refill = now;
+        }
+
+        state.tokens -= 1.0;
+        let ready_at = if state.tokens < 0.0 {
+            state.last_refill + Duration::from_secs_f64(-state.tokens / rate)
+        } else {
+            state.last_refill
+        };
+        let wait = ready_at.saturating_duration_since(now);
+
+        let metrics = &mut state.metrics;
+        metrics.requests += 1;
+        if !wait.is_zero() {
+            metrics.delayed_requests += 1;
+            metrics.total_wait += wait;
+            metrics.max_wait = metrics.max_wait.max(wait);
+        }
+        wait
+    }
+
+    /// Empty the bucket after the server reported that the rate limit was
+    /// exceeded. No tokens are handed out until `retry_after` has passed, or a
+    /// full period when the server did not say how long to wait.
+    pub(crate) fn back_off(&self, retry_after: Option<Duration>) {
+        let wait = retry_after.unwrap_or(self.period);
+        let mut state = self.lock();
+        state.tokens = 0.0;
+        state.last_refill = Instant::now() + wait;
+        state.metrics.rate_limited_responses += 1;
+    }
+
+    fn rate(&self) -> f64 {
+        self.capacity / self.period.as_secs_f64()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+impl Default for RateLimiter {
+    fn default() -> Self {
+        Self::per_minute(DEFAULT_REQUESTS_PER_MINUTE)
+    }
+}
+
+impl Debug for RateLimiter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RateLimiter")
+            .field("requests", &self.capacity)
+            .field("period", &self.period)
+            .field("max_retries", &self.max_retries)
+            .finish()
+    }
+}
+
+/// Returns `true` if `status` indicates the rate limit was exceeded.
+pub(crate) fn is_rate_limited(status: StatusCode) -> bool {
+    status.as_u16() == 420 || status == StatusCode::TOO_MANY_REQUESTS
+}
+
+/// Parse the `Retry-After` header, if it is given in seconds.
+pub(crate) fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    headers
+        .get(header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse()
+        .ok()
+        .map(Duration::from_secs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn full_bucket_does_not_wait() {
+        let limiter = RateLimiter::new(3, Duration::from_secs(60));
+        for _ in 0..3 {
+            assert_eq!(limiter.reserve(), Duration::ZERO);
+        }
+        let metrics = limiter.metrics();
+        assert_eq!(metrics.requests, 3);
+        assert_eq!(metrics.delayed_requests, 0);
+    }
+
+    #[test]
+    fn empty_bucket_waits_for_next_token() {
+        let limiter = RateLimiter::new(2, Duration::from_secs(60));
+        limiter.reserve();
+        limiter.reserve();
+        // One token is refilled every 30 seconds.
+        let wait = limiter.reserve();
+        assert!(wait > Duration::from_secs(29) && wait <= Duration::from_secs(30));
+        let wait = limiter.reserve();
+        assert!(wait > Duration::from_secs(59) && wait <= Duration::from_secs(60));
+
+        let metrics = limiter.metrics();
+        assert_eq!(metrics.delayed_requests, 2);
+        assert!(metrics.max_wait > Duration::from_secs(59));
+    }
+
+    #[test]
+    fn clones_share_the_bucket() {
+        let limiter = RateLimiter::new(1, Duration::from_secs(10));
+        let clone = limiter.clone();
+        assert_eq!(limiter.reserve(), Duration::ZERO);
+        assert!(clone.reserve() > Duration::from_secs(9));
+        assert_eq!(limiter.metrics().requests, 2);
+    }
+
+    #[test]
+    fn back_off_waits_for_retry_after() {
+        let limiter = RateLimiter::new(100, Duration::from_secs(60));
+        limiter.back_off(Some(Duration::from_secs(5)));
+        let wait = limiter.reserve();
+        assert!(wait > Duration::from_secs(5) && wait <= Duration::from_secs(6));
+        assert_eq!(limiter.metrics().rate_limited_responses, 1);
+    }
+
+    #[test]
+    fn back_off_without_retry_after_waits_a_period() {
+        let limiter = RateLimiter::new(100, Duration::from_secs(60));
+        limiter.back_off(None);
+        assert!(limiter.reserve() > Duration::from_secs(59));
+    }
+
+    #[test]
+    fn rate_limited_statuses() {
+        assert!(is_rate_limited(StatusCode::from_u16(420).unwrap()));
+        assert!(is_rate_limited(StatusCode::TOO_MANY_REQUESTS));
+        assert!(!is_rate_limited(StatusCode::SERVICE_UNAVAILABLE));
+    }
+
+    #[test]
+    fn parse_retry_after() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(retry_after(&headers), None);
+        headers.insert(header::RETRY_AFTER, " 5 ".parse().unwrap());
+        assert_eq!(retry_after(&headers), Some(Duration::from_secs(5)));
+        headers.insert(
+            header::RETRY_AFTER,
+            "Wed, 21 Oct 2015 07:28:00 GMT".parse().unwrap(),
+        );
+        assert_eq!(retry_after(&headers), None);
+    }
+}
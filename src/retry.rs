@@ -0,0 +1,369 @@
+//! Retrying requests that failed with a transient error.
+use std::{
+    collections::hash_map::RandomState,
+    convert::TryFrom,
+    hash::BuildHasher,
+    time::{Duration, Instant},
+};
+
+use http::{HeaderMap, Method, StatusCode};
+use log::warn;
+
+use crate::rate_limit::{self, RateLimiter};
+
+/// Policy deciding when, and how often, a failed request is sent again.
+///
+/// Requests are retried when sending them fails with a timeout or connection
+/// error, or when the server answers with one of the retryable status codes.
+/// The delay between attempts grows exponentially, with some random jitter to
+/// keep concurrent clients from retrying in lock-step.
+///
+/// By default only idempotent requests (`GET` and `DELETE`) are retried. Other
+/// requests, like submitting a run, are only retried after opting in with
+/// [`RetryPolicy::retry_non_idempotent`].
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    multiplier: f64,
+    jitter: f64,
+    retryable_statuses: Vec<StatusCode>,
+    retry_non_idempotent: bool,
+}
+
+impl RetryPolicy {
+    /// Create a new retry policy with the default settings.
+    ///
+    /// Requests are attempted at most 3 times, waiting 500ms before the first
+    /// retry and doubling the delay for every following retry, up to 30s. The
+    /// status codes `408`, `500`, `502`, `503` and `504` are retried.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Maximum number of attempts for a request, including the first one. A
+    /// value of `1` disables retrying.
+    pub fn max_attempts(mut self, value: u32) -> Self {
+        self.max_attempts = value.max(1);
+        self
+    }
+
+    /// Delay before the first retry, and the upper limit for the delay.
+    pub fn backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.initial_backoff = initial;
+        self.max_backoff = max.max(initial);
+        self
+    }
+
+    /// Factor the delay is multiplied with after every retry (default: 2.0).
+    pub fn multiplier(mut self, value: f64) -> Self {
+        self.multiplier = value.max(1.0);
+        self
+    }
+
+    /// Fraction of the delay that is randomized, between `0.0` (no jitter) and
+    /// `1.0` (default: 0.2).
+    pub fn jitter(mut self, value: f64) -> Self {
+        self.jitter = value.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Replace the status codes that are considered transient.
+    pub fn retryable_statuses<I>(mut self, iter: I) -> Self
+    where
+        I: IntoIterator<Item = StatusCode>,
+    {
+        self.retryable_statuses = iter.into_iter().collect();
+        self
+    }
+
+    /// When `true`, requests with non-idempotent methods (like `POST` and
+    /// `PUT`) are retried as well (default: `false`).
+    ///
+    /// Retrying these requests can apply them twice, e.g. submitting the same
+    /// run two times.
+    pub fn retry_non_idempotent(mut self, value: bool) -> Self {
+        self.retry_non_idempotent = value;
+        self
+    }
+
+    fn allows(&self, method: &Method) -> bool {
+        self.retry_non_idempotent || is_idempotent(method)
+    }
+
+    fn is_retryable_status(&self, status: StatusCode) -> bool {
+        self.retryable_statuses.contains(&status)
+    }
+
+    /// Only errors that happened before the server could act on the request
+    /// (failing to connect) or that are likely transient (timeouts) are
+    /// retried.
+    fn is_retryable_error(&self, err: &reqwest::Error) -> bool {
+        err.is_timeout() || err.is_connect()
+    }
+
+    /// Delay before the given retry (starting at 1), including jitter.
+    fn delay(&self, retry: u32) -> Duration {
+        let exponent = i32::try_from(retry.saturating_sub(1)).unwrap_or(i32::MAX);
+        let backoff = self.initial_backoff.as_secs_f64() * self.multiplier.powi(exponent);
+        let backoff = backoff.min(self.max_backoff.as_secs_f64());
+        Duration::from_secs_f64(backoff * (1.0 - self.jitter * random_fraction()))
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+            multiplier: 2.0,
+            jitter: 0.2,
+            retryable_statuses: vec![
+                StatusCode::REQUEST_TIMEOUT,
+                StatusCode::INTERNAL_SERVER_ERROR,
+                StatusCode::BAD_GATEWAY,
+                StatusCode::SERVICE_UNAVAILABLE,
+                StatusCode::GATEWAY_TIMEOUT,
+            ],
+            retry_non_idempotent: false,
+        }
+    }
+}
+
+/// Tracks the attempts made to send a single request.
+pub(crate) struct Attempts<'a> {
+    rate_limit: Option<&'a RateLimiter>,
+    retry: Option<&'a RetryPolicy>,
+    /// Whether the request may be resent at all, see [`RetryPolicy::allows`].
+    resend: bool,
+    rate_limited: u32,
+    attempt: u32,
+}
+
+impl<'a> Attempts<'a> {
+    pub(crate) fn new(
+        rate_limit: Option<&'a RateLimiter>,
+        retry: Option<&'a RetryPolicy>,
+        method: &Method,
+    ) -> Self {
+        let resend = retry.map_or_else(|| is_idempotent(method), |policy| policy.allows(method));
+        Self {
+            rate_limit,
+            retry: retry.filter(|_| resend),
+            resend,
+            rate_limited: 0,
+            attempt: 1,
+        }
+    }
+
+    /// Returns `true` if the request may be sent again after this attempt.
+    pub(crate) fn can_retry(&self) -> bool {
+        self.rate_limit
+            .is_some_and(|limiter| self.resend && self.rate_limited < limiter.retries())
+            || self
+                .retry
+                .is_some_and(|policy| self.attempt < policy.max_attempts)
+    }
+
+    /// How long to wait before sending the next attempt.
+    pub(crate) fn wait(&self) -> Duration {
+        self.rate_limit.map_or(Duration::ZERO, RateLimiter::reserve)
+    }
+
+    /// Decide if a request should be retried after receiving a response.
+    /// Returns the delay before the retry.
+    pub(crate) fn retry_response(
+        &mut self,
+        status: StatusCode,
+        headers: &HeaderMap,
+    ) -> Option<Duration> {
+        let retry_after = rate_limit::retry_after(headers);
+        if rate_limit::is_rate_limited(status) {
+            if let Some(limiter) = self.rate_limit {
+                // The limiter holds back the next attempt, and any other
+                // request made in the meantime.
+                limiter.back_off(retry_after);
+                if self.resend && self.rate_limited < limiter.retries() {
+                    warn!("rate limit exceeded, retry after {:?}", retry_after);
+                    self.rate_limited += 1;
+                    return Some(Duration::ZERO);
+                }
+            }
+        }
+        let policy = self.retry?;
+        if self.attempt < policy.max_attempts && policy.is_retryable_status(status) {
+            let delay = policy.delay(self.attempt);
+            let delay = retry_after.map_or(delay, |retry_after| delay.max(retry_after));
+            warn!(
+                "request failed with status {}, retrying in {:?}",
+                status, delay
+            );
+            self.attempt += 1;
+            Some(delay)
+        } else {
+            None
+        }
+    }
+
+    /// Decide if a request should be retried after failing to send it.
+    /// Returns the delay before the retry.
+    pub(crate) fn retry_error(&mut self, err: &reqwest::Error) -> Option<Duration> {
+        let policy = self.retry?;
+        if self.attempt < policy.max_attempts && policy.is_retryable_error(err) {
+            let delay = policy.delay(self.attempt);
+            warn!("request failed: {}, retrying in {:?}", err, delay);
+            self.attempt += 1;
+            Some(delay)
+        } else {
+            None
+        }
+    }
+}
+
+/// Requests that can be sent again without applying them twice.
+fn is_idempotent(method: &Method) -> bool {
+    *method == Method::GET || *method == Method::DELETE
+}
+
+/// A random number in `0.0..1.0`, good enough for jitter.
+fn random_fraction() -> f64 {
+    let hash = RandomState::new().hash_one(Instant::now());
+    (hash >> 11) as f64 / (1u64 << 53) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        api::{endpoint::Endpoint, runs::CreateRun},
+        types::RunTime,
+    };
+
+    fn policy() -> RetryPolicy {
+        RetryPolicy::new()
+            .backoff(Duration::from_secs(1), Duration::from_secs(5))
+            .jitter(0.0)
+    }
+
+    #[test]
+    fn delay_grows_exponentially_up_to_max() {
+        let policy = policy();
+        assert_eq!(policy.delay(1), Duration::from_secs(1));
+        assert_eq!(policy.delay(2), Duration::from_secs(2));
+        assert_eq!(policy.delay(3), Duration::from_secs(4));
+        assert_eq!(policy.delay(4), Duration::from_secs(5));
+        assert_eq!(policy.delay(100), Duration::from_secs(5));
+    }
+
+    #[test]
+    fn jitter_shortens_delay() {
+        let policy = policy().jitter(0.5);
+        for _ in 0..20 {
+            let delay = policy.delay(1);
+            assert!(delay > Duration::from_millis(500) && delay <= Duration::from_secs(1));
+        }
+    }
+
+    #[test]
+    fn retries_transient_statuses_up_to_max_attempts() {
+        let policy = policy().max_attempts(3);
+        let mut attempts = Attempts::new(None, Some(&policy), &Method::GET);
+        let headers = HeaderMap::new();
+        assert!(attempts.can_retry());
+        assert_eq!(
+            attempts.retry_response(StatusCode::BAD_GATEWAY, &headers),
+            Some(Duration::from_secs(1))
+        );
+        assert_eq!(
+            attempts.retry_response(StatusCode::BAD_GATEWAY, &headers),
+            Some(Duration::from_secs(2))
+        );
+        assert!(!attempts.can_retry());
+        assert_eq!(
+            attempts.retry_response(StatusCode::BAD_GATEWAY, &headers),
+            None
+        );
+    }
+
+    #[test]
+    fn does_not_retry_other_statuses() {
+        let policy = policy();
+        let mut attempts = Attempts::new(None, Some(&policy), &Method::GET);
+        let headers = HeaderMap::new();
+        assert_eq!(
+            attempts.retry_response(StatusCode::NOT_FOUND, &headers),
+            None
+        );
+    }
+
+    #[test]
+    fn retry_after_extends_delay() {
+        let policy = policy();
+        let mut attempts = Attempts::new(None, Some(&policy), &Method::GET);
+        let mut headers = HeaderMap::new();
+        headers.insert(http::header::RETRY_AFTER, "3".parse().unwrap());
+        assert_eq!(
+            attempts.retry_response(StatusCode::SERVICE_UNAVAILABLE, &headers),
+            Some(Duration::from_secs(3))
+        );
+    }
+
+    #[test]
+    fn non_idempotent_requests_need_opt_in() {
+        let policy = policy();
+        let attempts = Attempts::new(None, Some(&policy), &Method::POST);
+        assert!(!attempts.can_retry());
+
+        let policy = policy.retry_non_idempotent(true);
+        let attempts = Attempts::new(None, Some(&policy), &Method::POST);
+        assert!(attempts.can_retry());
+    }
+
+    #[test]
+    fn rate_limited_responses_back_off_the_limiter() {
+        let limiter = RateLimiter::new(100, Duration::from_secs(60)).max_retries(1);
+        let mut attempts = Attempts::new(Some(&limiter), None, &Method::GET);
+        let status = StatusCode::from_u16(420).unwrap();
+        let headers = HeaderMap::new();
+        assert_eq!(
+            attempts.retry_response(status, &headers),
+            Some(Duration::ZERO)
+        );
+        assert!(attempts.wait() > Duration::from_secs(59));
+        assert!(!attempts.can_retry());
+        assert_eq!(attempts.retry_response(status, &headers), None);
+    }
+
+    #[test]
+    fn rate_limited_submissions_are_not_resent_by_default() {
+        let run = CreateRun::builder()
+            .category("wkpoo02r")
+            .realtime(RunTime::from_millis(1_000))
+            .build()
+            .unwrap();
+        let method = run.method();
+        let limiter = RateLimiter::new(100, Duration::from_secs(60));
+        let status = StatusCode::TOO_MANY_REQUESTS;
+        let headers = HeaderMap::new();
+
+        let mut attempts = Attempts::new(Some(&limiter), None, &method);
+        assert!(!attempts.can_retry());
+        assert_eq!(attempts.retry_response(status, &headers), None);
+        // Other requests still wait for the rate limit to reset.
+        assert!(attempts.wait() > Duration::from_secs(59));
+
+        let policy = policy();
+        let mut attempts = Attempts::new(Some(&limiter), Some(&policy), &method);
+        assert_eq!(attempts.retry_response(status, &headers), None);
+
+        let policy = policy.retry_non_idempotent(true);
+        let mut attempts = Attempts::new(Some(&limiter), Some(&policy), &method);
+        assert!(attempts.can_retry());
+        assert_eq!(
+            attempts.retry_response(status, &headers),
+            Some(Duration::ZERO)
+        );
+    }
+}
@@ -0,0 +1,141 @@
+//! Fixtures shared by the unit tests.
+use serde_json::{json, Value};
+
+use crate::types::{Run, RunTime, Variable};
+
+/// The JSON of a verified run of category `wkpoo02r` of game `o1y9wo6q`,
+/// played by a user with the same `ID` as the run. Fields in `extra` replace
+/// the defaults.
+pub(crate) fn run_json(id: &str, realtime: Option<f64>, date: Option<&str>, extra: Value) -> Value {
+    let mut run = json!({
+        "id": id,
+        "weblink": format!("https://www.speedrun.com/run/{}", id),
+        "game": "o1y9wo6q",
+        "category": "wkpoo02r",
+        "status": { "status": "verified", "examiner": null, "verify-date": null },
+        "players": [{ "rel": "user", "id": id, "uri": "https://www.speedrun.com/api/v1/users/x" }],
+        "date": date,
+        "times": times_json(realtime, None),
+        "system": { "platform": null, "emulated": false, "region": null },
+    });
+    if let (Some(run), Value::Object(extra)) = (run.as_object_mut(), extra) {
+        run.extend(extra);
+    }
+    run
+}
+
+/// The JSON of the times of a run, in seconds, the way the API sends them:
+/// ISO 8601 durations along with the number of seconds.
+pub(crate) fn times_json(realtime: Option<f64>, ingame: Option<f64>) -> Value {
+    let iso = |seconds: Option<f64>| {
+        seconds.map(|seconds| RunTime::from_millis((seconds * 1000.0).round() as i64).to_iso8601())
+    };
+    let primary = realtime.or(ingame).unwrap_or(0.0);
+    json!({
+        "primary": iso(Some(primary)),
+        "primary_t": primary,
+        "realtime": iso(realtime),
+        "realtime_t": realtime.unwrap_or(0.0),
+        "realtime_noloads": null,
+        "realtime_noloads_t": 0,
+        "ingame": iso(ingame),
+        "ingame_t": ingame.unwrap_or(0.0),
+    })
+}
+
+/// See [`run_json`].
+pub(crate) fn run_with(
+    id: &str,
+    realtime: Option<f64>,
+    date: Option<&str>,
+    extra: Value,
+) -> Run<'static> {
+    serde_json::from_value(run_json(id, realtime, date, extra)).unwrap()
+}
+
+/// See [`run_json`].
+pub(crate) fn run(id: &str, realtime: Option<f64>, date: Option<&str>) -> Run<'static> {
+    run_with(id, realtime, date, json!({}))
+}
+
+/// The players of a run played by a single guest.
+pub(crate) fn guest(name: &str) -> Value {
+    json!([{ "rel": "guest", "name": name, "uri": "https://www.speedrun.com/api/v1/guests/x" }])
+}
+
+/// The JSON of a variable with the values `a` (the default) and `b`.
+pub(crate) fn variable_json(id: &str, subcategory: bool, obsoletes: bool, scope: Value) -> Value {
+    json!({
+        "id": id,
+        "name": id,
+        "category": null,
+        "scope": scope,
+        "mandatory": false,
+        "user-defined": false,
+        "obsoletes": obsoletes,
+        "values": {
+            "values": { "a": { "label": "A" }, "b": { "label": "B" } },
+            "default": "a",
+        },
+        "is-subcategory": subcategory,
+        "links": [],
+    })
+}
+
+/// See [`variable_json`].
+pub(crate) fn variable(
+    id: &str,
+    subcategory: bool,
+    obsoletes: bool,
+    scope: Value,
+) -> Variable<'static> {
+    serde_json::from_value(variable_json(id, subcategory, obsoletes, scope)).unwrap()
+}
+
+/// The JSON of a game timed by real time, showing milliseconds.
+pub(crate) fn game_json(id: &str) -> Value {
+    json!({
+        "id": id,
+        "names": { "international": id },
+        "abbreviation": id,
+        "weblink": format!("https://www.speedrun.com/{}", id),
+        "release-date": "1996-06-23",
+        "ruleset": {
+            "show-milliseconds": true,
+            "require-verification": true,
+            "require-video": false,
+            "run-times": ["realtime"],
+            "default-time": "realtime",
+            "emulators-allowed": false,
+        },
+        "gametypes": [],
+        "platforms": [],
+        "regions": [],
+        "genres": [],
+        "engines": [],
+        "developers": [],
+        "publishers": [],
+        "moderators": {},
+        "created": null,
+        "assets": {
+            "logo": {},
+            "cover-tiny": {},
+            "cover-small": {},
+            "cover-medium": {},
+            "cover-large": {},
+            "icon": {},
+            "trophy-1st": {},
+            "trophy-2nd": {},
+            "trophy-3rd": {},
+        },
+        "links": [],
+    })
+}
+
+/// A single, complete page of a paginated response.
+pub(crate) fn page<T: serde::Serialize>(data: Vec<T>) -> Value {
+    json!({
+        "data": data,
+        "pagination": { "offset": 0, "max": 200, "size": data.len(), "links": [] },
+    })
+}
@@ -3,8 +3,13 @@
 mod category;
 mod category_impls;
 mod common;
+mod common_impls;
+mod date_impls;
+mod dates;
 mod developer_impls;
 mod developers;
+mod embed_impls;
+mod embeds;
 mod engine_impls;
 mod engines;
 mod game_impls;
@@ -27,6 +32,8 @@ mod publishers;
 mod region_impls;
 mod regions;
 mod run_impls;
+mod run_time;
+mod run_time_impls;
 mod runs;
 mod series;
 mod series_impls;
@@ -35,23 +42,27 @@ mod users;
 mod variable_impls;
 mod variables;
 
-// TODO: Deserialize to URI/URL type
-// TODO: Deserialize dates to chrono types
-
 pub use category::{Category, CategoryType, Players};
 pub use common::{Asset, Assets, Link, ModeratorRole, Names, Pagination, TimingMethod, Root};
+pub use dates::{Date, DateError, DateTime};
 pub use developers::Developer;
+pub use embeds::{
+    CategoryWithEmbeds, Embeddable, Embedded, EmbeddedPlayer, GameWithEmbeds, LeaderboardWithEmbeds,
+    OptionalEmbed, RunWithEmbeds,
+};
 pub use engines::Engine;
-pub use games::{Game, Ruleset};
+pub use games::{BulkGame, Game, Ruleset};
 pub use gametypes::GameType;
 pub use genres::Genre;
 pub use guests::Guest;
 pub use leaderboards::{Leaderboard, RankedRun};
 pub use levels::Level;
+pub use notifications::{Item, Notification, ReadStatus};
 pub use platforms::Platform;
 pub use publishers::Publisher;
 pub use regions::Region;
-pub use runs::{Player, Run, Status, System, Times, VideoLink, Videos};
+pub use run_time::{RunTime, RunTimeError};
+pub use runs::{Player, Run, Status, System, Times, VideoLink, VideoUri, Videos};
 pub use series::Series;
 pub use users::{BasicLink, Color, Location, NameStyle, Place, User, UserRole};
 pub use variables::{Flags, Scope, Value, Values, Variable};
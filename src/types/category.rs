@@ -1,4 +1,5 @@
 use serde::Deserialize;
+use url::Url;
 
 use crate::api::categories::CategoryId;
 
@@ -9,7 +10,7 @@ use super::Link;
 pub struct Category<'a> {
     pub id: CategoryId<'a>,
     pub name: String,
-    pub weblink: String,
+    pub weblink: Url,
     #[serde(rename = "type")]
     pub type_field: CategoryType,
     pub rules: String,
@@ -1,10 +1,11 @@
 use serde::{Deserialize, Serialize};
+use url::Url;
 
-#[derive(Default, Debug, Clone, PartialEq, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct Link {
     pub rel: String,
-    pub uri: String,
+    pub uri: Url,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Deserialize)]
@@ -16,7 +17,7 @@ pub struct Pagination {
     pub links: Vec<Link>,
 }
 
-#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
 #[serde(rename_all = "snake_case")]
 pub enum TimingMethod {
     Realtime,
@@ -51,12 +52,12 @@ pub struct Assets {
 #[derive(Default, Debug, Clone, PartialEq, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct Asset {
-    pub uri: Option<String>,
+    pub uri: Option<Url>,
     pub width: Option<i64>,
     pub height: Option<i64>,
 }
 
-#[derive(Default, Debug, Clone, PartialEq, Deserialize)]
+#[derive(Default, Debug, Clone, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct Names {
     pub international: String,
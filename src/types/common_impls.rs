@@ -0,0 +1,38 @@
+use crate::api::links::{LinkEndpoint, LinkTarget};
+
+use super::{Link, Pagination};
+
+impl Link {
+    /// Resolve this link into the endpoint it points to.
+    ///
+    /// The endpoint is determined from the `uri`, e.g. a `game` link with the
+    /// URI `.../api/v1/games/v1pxjz68` resolves to [`LinkTarget::Game`]. A
+    /// link whose `uri` does not match its `rel` resolves to
+    /// [`LinkTarget::Other`].
+    pub fn resolve(&self) -> LinkTarget {
+        LinkTarget::from_link(&self.rel, &self.uri)
+    }
+
+    /// An endpoint retrieving the resource this link points to. Returns `None`
+    /// if the link does not point to the API.
+    pub fn endpoint(&self) -> Option<LinkEndpoint> {
+        LinkEndpoint::new(self)
+    }
+}
+
+impl Pagination {
+    /// Returns the link with the given `rel`, if present.
+    pub fn link(&self, rel: &str) -> Option<&Link> {
+        self.links.iter().find(|link| link.rel == rel)
+    }
+
+    /// Returns the link to the next page, if there is one.
+    pub fn next(&self) -> Option<&Link> {
+        self.link("next")
+    }
+
+    /// Returns the link to the previous page, if there is one.
+    pub fn prev(&self) -> Option<&Link> {
+        self.link("prev")
+    }
+}
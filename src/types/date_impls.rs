@@ -0,0 +1,383 @@
+use std::{convert::TryFrom, fmt::Display, str::FromStr};
+
+use chrono::{Datelike, NaiveDate, NaiveDateTime, SecondsFormat, SubsecRound, Timelike, Utc};
+use log::warn;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+use super::{Date, DateError, DateTime};
+
+impl Date {
+    /// Create a new date.
+    ///
+    /// # Errors
+    ///
+    /// If the date does not exist, or the year is not between 0 and 9999.
+    pub fn new(year: i32, month: u32, day: u32) -> Result<Self, DateError> {
+        NaiveDate::from_ymd_opt(year, month, day)
+            .filter(|date| in_range(*date))
+            .map(Date)
+            .ok_or_else(|| DateError::date(format!("{}-{}-{}", year, month, day)))
+    }
+
+    /// The year.
+    pub fn year(&self) -> i32 {
+        self.0.year()
+    }
+
+    /// The month, starting at 1.
+    pub fn month(&self) -> u32 {
+        self.0.month()
+    }
+
+    /// The day of the month, starting at 1.
+    pub fn day(&self) -> u32 {
+        self.0.day()
+    }
+
+    /// The start of this day, in UTC.
+    pub fn and_midnight(&self) -> DateTime {
+        DateTime(self.0.and_time(Default::default()).and_utc())
+    }
+}
+
+impl DateTime {
+    /// Create a timestamp from the number of seconds since
+    /// `1970-01-01T00:00:00Z`.
+    ///
+    /// # Errors
+    ///
+    /// If the timestamp is not between the years 0 and 9999.
+    pub fn from_timestamp(timestamp: i64) -> Result<Self, DateError> {
+        chrono::DateTime::from_timestamp(timestamp, 0)
+            .and_then(DateTime::from_utc)
+            .ok_or_else(|| DateError::date_time(timestamp.to_string()))
+    }
+
+    /// Number of seconds since `1970-01-01T00:00:00Z`.
+    pub fn timestamp(&self) -> i64 {
+        self.0.timestamp()
+    }
+
+    /// The date, in UTC.
+    pub fn date(&self) -> Date {
+        Date(self.0.date_naive())
+    }
+
+    /// The hour of the day, in UTC.
+    pub fn hour(&self) -> u32 {
+        self.0.hour()
+    }
+
+    /// The minute of the hour.
+    pub fn minute(&self) -> u32 {
+        self.0.minute()
+    }
+
+    /// The second of the minute.
+    pub fn second(&self) -> u32 {
+        self.0.second()
+    }
+
+    fn from_utc(time: chrono::DateTime<Utc>) -> Option<Self> {
+        in_range(time.date_naive()).then(|| DateTime(time.trunc_subsecs(0)))
+    }
+}
+
+impl DateError {
+    fn date(input: impl Into<String>) -> Self {
+        Self {
+            kind: "date",
+            input: input.into(),
+        }
+    }
+
+    fn date_time(input: impl Into<String>) -> Self {
+        Self {
+            kind: "timestamp",
+            input: input.into(),
+        }
+    }
+}
+
+// Dates are formatted with four digit years by the API.
+fn in_range(date: NaiveDate) -> bool {
+    (0..=9999).contains(&date.year())
+}
+
+impl FromStr for Date {
+    type Err = DateError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // chrono accepts any number of digits, `21-4-1` would be the year 21.
+        let is_iso = s.len() == 10
+            && s.bytes().enumerate().all(|(i, b)| {
+                if i == 4 || i == 7 {
+                    b == b'-'
+                } else {
+                    b.is_ascii_digit()
+                }
+            });
+        NaiveDate::parse_from_str(s, "%Y-%m-%d")
+            .ok()
+            .filter(|date| is_iso && in_range(*date))
+            .map(Date)
+            .ok_or_else(|| DateError::date(s))
+    }
+}
+
+impl FromStr for DateTime {
+    type Err = DateError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        chrono::DateTime::parse_from_rfc3339(s)
+            .map(|time| time.with_timezone(&Utc))
+            .or_else(|_| {
+                NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S%.f").map(|time| time.and_utc())
+            })
+            .ok()
+            .and_then(DateTime::from_utc)
+            .ok_or_else(|| DateError::date_time(s))
+    }
+}
+
+impl Display for Date {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.format("%Y-%m-%d").fmt(f)
+    }
+}
+
+impl Display for DateTime {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0.to_rfc3339_opts(SecondsFormat::Secs, true))
+    }
+}
+
+impl From<DateTime> for Date {
+    fn from(value: DateTime) -> Self {
+        value.date()
+    }
+}
+
+impl From<Date> for DateTime {
+    fn from(value: Date) -> Self {
+        value.and_midnight()
+    }
+}
+
+impl From<Date> for NaiveDate {
+    fn from(value: Date) -> Self {
+        value.0
+    }
+}
+
+impl TryFrom<NaiveDate> for Date {
+    type Error = DateError;
+
+    fn try_from(value: NaiveDate) -> Result<Self, Self::Error> {
+        if in_range(value) {
+            Ok(Date(value))
+        } else {
+            Err(DateError::date(value.to_string()))
+        }
+    }
+}
+
+impl From<DateTime> for chrono::DateTime<Utc> {
+    fn from(value: DateTime) -> Self {
+        value.0
+    }
+}
+
+impl TryFrom<chrono::DateTime<Utc>> for DateTime {
+    type Error = DateError;
+
+    fn try_from(value: chrono::DateTime<Utc>) -> Result<Self, Self::Error> {
+        DateTime::from_utc(value).ok_or_else(|| DateError::date_time(value.to_rfc3339()))
+    }
+}
+
+impl Serialize for Date {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl Serialize for DateTime {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Date {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = String::deserialize(deserializer)?;
+        value.parse().map_err(de::Error::custom)
+    }
+}
+
+impl<'de> Deserialize<'de> for DateTime {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = String::deserialize(deserializer)?;
+        value.parse().map_err(de::Error::custom)
+    }
+}
+
+/// Deserialize an optional date of a response, ignoring values that can not be
+/// parsed instead of failing the whole resource.
+pub(super) fn lenient<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr<Err = DateError>,
+{
+    let value = Option::<String>::deserialize(deserializer)?;
+    Ok(value.and_then(|value| value.parse().map_err(|err| warn!("ignoring {}", err)).ok()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_dates() {
+        let date: Date = "2021-02-28".parse().unwrap();
+        assert_eq!((date.year(), date.month(), date.day()), (2021, 2, 28));
+        assert_eq!(date.to_string(), "2021-02-28");
+
+        assert!("2020-02-29".parse::<Date>().is_ok());
+        assert!("2000-02-29".parse::<Date>().is_ok());
+        for invalid in &[
+            "2021-02-29",
+            "1900-02-29",
+            "2021-13-01",
+            "2021-00-10",
+            "2021-04-31",
+            "2021-4-01",
+            "21-04-01",
+            "+021-04-01",
+            "2021-04-01T00:00:00Z",
+        ] {
+            let err = invalid.parse::<Date>().unwrap_err();
+            assert_eq!(err.to_string(), format!("invalid date `{}`", invalid));
+        }
+    }
+
+    #[test]
+    fn date_bounds() {
+        assert!(Date::new(-1, 1, 1).is_err());
+        assert!(Date::new(10000, 1, 1).is_err());
+        assert_eq!(Date::new(0, 1, 1).unwrap().to_string(), "0000-01-01");
+        assert!(DateTime::from_timestamp(-62_167_219_201).is_err());
+        assert_eq!(
+            DateTime::from_timestamp(1_622_550_896).unwrap().to_string(),
+            "2021-06-01T12:34:56Z"
+        );
+    }
+
+    #[test]
+    fn parse_date_times() {
+        let time: DateTime = "2021-06-01T12:34:56Z".parse().unwrap();
+        assert_eq!(time.timestamp(), 1_622_550_896);
+        assert_eq!((time.hour(), time.minute(), time.second()), (12, 34, 56));
+        assert_eq!(time.to_string(), "2021-06-01T12:34:56Z");
+
+        let same = [
+            "2021-06-01 12:34:56Z",
+            "2021-06-01T12:34:56.789Z",
+            "2021-06-01T14:34:56+02:00",
+            "2021-06-01T10:04:56-02:30",
+            "2021-06-01T12:34:56",
+        ];
+        for input in &same {
+            assert_eq!(input.parse::<DateTime>().unwrap(), time, "{}", input);
+        }
+    }
+
+    #[test]
+    fn offsets_can_cross_days() {
+        let time: DateTime = "2021-01-01T01:00:00+02:00".parse().unwrap();
+        assert_eq!(time.to_string(), "2020-12-31T23:00:00Z");
+        assert_eq!(time.date(), Date::new(2020, 12, 31).unwrap());
+        assert_eq!(
+            DateTime::from(time.date()).to_string(),
+            "2020-12-31T00:00:00Z"
+        );
+    }
+
+    #[test]
+    fn invalid_date_times() {
+        for invalid in &[
+            "2021-06-01",
+            "2021-06-01T24:00:00Z",
+            "2021-06-01T12:60:00Z",
+            "2021-06-01T12:34:56.Z",
+            "2021-06-01T12:34:56+24:00",
+            "0000-01-01T00:00:00+00:01",
+        ] {
+            let err = invalid.parse::<DateTime>().unwrap_err();
+            assert_eq!(err.to_string(), format!("invalid timestamp `{}`", invalid));
+        }
+    }
+
+    #[test]
+    fn deserialize_and_serialize() {
+        let date: Date = serde_json::from_str("\"2021-06-01\"").unwrap();
+        assert_eq!(serde_json::to_string(&date).unwrap(), "\"2021-06-01\"");
+
+        let err = serde_json::from_str::<Date>("\"2021-06-31\"").unwrap_err();
+        assert!(err.to_string().contains("invalid date `2021-06-31`"));
+
+        let time: DateTime = serde_json::from_str("\"2021-06-01T12:34:56+01:00\"").unwrap();
+        assert_eq!(
+            serde_json::to_string(&time).unwrap(),
+            "\"2021-06-01T11:34:56Z\""
+        );
+    }
+
+    #[test]
+    fn unusual_dates_are_ignored_in_responses() {
+        #[derive(Deserialize)]
+        struct Response {
+            #[serde(default, deserialize_with = "lenient")]
+            date: Option<Date>,
+        }
+
+        let parse = |value| {
+            serde_json::from_value::<Response>(value)
+                .unwrap()
+                .date
+                .map(|date| date.to_string())
+        };
+        assert_eq!(
+            parse(serde_json::json!({ "date": "2021-06-01" })),
+            Some("2021-06-01".into())
+        );
+        assert_eq!(parse(serde_json::json!({ "date": "2021-06-31" })), None);
+        assert_eq!(parse(serde_json::json!({ "date": null })), None);
+        assert_eq!(parse(serde_json::json!({})), None);
+    }
+
+    #[test]
+    fn chrono_conversions() {
+        let date = Date::new(2021, 6, 1).unwrap();
+        let naive = NaiveDate::from(date);
+        assert_eq!(Date::try_from(naive).unwrap(), date);
+        assert!(Date::try_from(NaiveDate::from_ymd_opt(10000, 1, 1).unwrap()).is_err());
+
+        let time: DateTime = "2021-06-01T12:34:56Z".parse().unwrap();
+        let chrono = chrono::DateTime::<Utc>::from(time);
+        assert_eq!(chrono.timestamp(), time.timestamp());
+        assert_eq!(DateTime::try_from(chrono).unwrap(), time);
+    }
+}
@@ -0,0 +1,25 @@
+use thiserror::Error;
+
+/// A calendar date (`YYYY-MM-DD`), between the years 0 and 9999.
+///
+/// Dates are validated when they are created, so an invalid date is rejected
+/// before it is sent to the API. Convert it to a [`chrono::NaiveDate`] for
+/// date arithmetic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date(pub(super) chrono::NaiveDate);
+
+/// A point in time in UTC, with a precision of one second.
+///
+/// Timestamps are formatted as `YYYY-MM-DDTHH:MM:SSZ`. When parsing, a UTC
+/// offset (`+HH:MM`) is accepted in place of `Z` and a timestamp without an
+/// offset is assumed to be in UTC. Fractional seconds are discarded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DateTime(pub(super) chrono::DateTime<chrono::Utc>);
+
+/// Error returned for invalid dates and timestamps.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("invalid {kind} `{input}`")]
+pub struct DateError {
+    pub(super) kind: &'static str,
+    pub(super) input: String,
+}
@@ -0,0 +1,215 @@
+use crate::api::{categories::CategoryId, games::GameId, runs::RunId};
+
+use super::{
+    CategoryWithEmbeds, Developer, Embeddable, EmbeddedPlayer, Engine, GameType, GameWithEmbeds,
+    Genre, LeaderboardWithEmbeds, Level, OptionalEmbed, Platform, Publisher, Region, RunWithEmbeds,
+    User, Variable,
+};
+
+impl<I, T> Embeddable<I, T> {
+    /// Returns the embedded resource, if it was embedded.
+    pub fn embedded(&self) -> Option<&T> {
+        match self {
+            Embeddable::Embedded(embedded) => Some(&embedded.data),
+            Embeddable::Id(_) => None,
+        }
+    }
+
+    /// Returns the reference to the resource, if it was not embedded.
+    pub fn id(&self) -> Option<&I> {
+        match self {
+            Embeddable::Embedded(_) => None,
+            Embeddable::Id(id) => Some(id),
+        }
+    }
+
+    /// Converts into the embedded resource, if it was embedded.
+    pub fn into_embedded(self) -> Option<T> {
+        match self {
+            Embeddable::Embedded(embedded) => Some(embedded.data),
+            Embeddable::Id(_) => None,
+        }
+    }
+}
+
+impl<T> OptionalEmbed<T> {
+    /// Returns the embedded resource, if one is set.
+    pub fn as_ref(&self) -> Option<&T> {
+        self.0.as_ref()
+    }
+}
+
+impl<T> From<OptionalEmbed<T>> for Option<T> {
+    fn from(value: OptionalEmbed<T>) -> Self {
+        value.0
+    }
+}
+
+impl<'a> RunWithEmbeds<'a> {
+    /// The embedded game (`RunEmbeds::Game`).
+    pub fn game(&self) -> Option<&GameWithEmbeds<'a>> {
+        self.game.embedded()
+    }
+
+    /// The embedded category (`RunEmbeds::Category`).
+    pub fn category(&self) -> Option<&CategoryWithEmbeds<'a>> {
+        self.category.embedded()
+    }
+
+    /// The embedded level (`RunEmbeds::Level`). `None` for full-game runs.
+    pub fn level(&self) -> Option<&Level<'a>> {
+        self.level.as_ref()?.embedded()?.as_ref()
+    }
+
+    /// The embedded players (`RunEmbeds::Players`).
+    pub fn players(&self) -> Option<&[EmbeddedPlayer<'a>]> {
+        self.players.embedded().map(Vec::as_slice)
+    }
+
+    /// The embedded platform (`RunEmbeds::Platform`), if one was set.
+    pub fn platform(&self) -> Option<&Platform<'a>> {
+        self.platform.as_ref()?.data.as_ref()
+    }
+
+    /// The embedded region (`RunEmbeds::Region`), if one was set.
+    pub fn region(&self) -> Option<&Region<'a>> {
+        self.region.as_ref()?.data.as_ref()
+    }
+}
+
+impl<'a> From<RunWithEmbeds<'a>> for RunId<'a> {
+    fn from(value: RunWithEmbeds<'a>) -> Self {
+        value.id
+    }
+}
+
+impl<'a> GameWithEmbeds<'a> {
+    /// The embedded levels (`GameEmbeds::Levels`).
+    pub fn levels(&self) -> Option<&[Level<'a>]> {
+        self.levels.as_ref().map(|levels| levels.data.as_slice())
+    }
+
+    /// The embedded categories (`GameEmbeds::Categories`).
+    pub fn categories(&self) -> Option<&[CategoryWithEmbeds<'a>]> {
+        self.categories
+            .as_ref()
+            .map(|categories| categories.data.as_slice())
+    }
+
+    /// The embedded variables (`GameEmbeds::Variables`).
+    pub fn variables(&self) -> Option<&[Variable<'a>]> {
+        self.variables
+            .as_ref()
+            .map(|variables| variables.data.as_slice())
+    }
+
+    /// The embedded moderators (`GameEmbeds::Moderators`).
+    pub fn moderators(&self) -> Option<&[User<'a>]> {
+        self.moderators.embedded().map(Vec::as_slice)
+    }
+
+    /// The embedded gametypes (`GameEmbeds::Gametypes`).
+    pub fn gametypes(&self) -> Option<&[GameType<'a>]> {
+        self.gametypes.embedded().map(Vec::as_slice)
+    }
+
+    /// The embedded platforms (`GameEmbeds::Platforms`).
+    pub fn platforms(&self) -> Option<&[Platform<'a>]> {
+        self.platforms.embedded().map(Vec::as_slice)
+    }
+
+    /// The embedded regions (`GameEmbeds::Regions`).
+    pub fn regions(&self) -> Option<&[Region<'a>]> {
+        self.regions.embedded().map(Vec::as_slice)
+    }
+
+    /// The embedded genres (`GameEmbeds::Genres`).
+    pub fn genres(&self) -> Option<&[Genre<'a>]> {
+        self.genres.embedded().map(Vec::as_slice)
+    }
+
+    /// The embedded engines (`GameEmbeds::Engines`).
+    pub fn engines(&self) -> Option<&[Engine<'a>]> {
+        self.engines.embedded().map(Vec::as_slice)
+    }
+
+    /// The embedded developers (`GameEmbeds::Developers`).
+    pub fn developers(&self) -> Option<&[Developer<'a>]> {
+        self.developers.embedded().map(Vec::as_slice)
+    }
+
+    /// The embedded publishers (`GameEmbeds::Publishers`).
+    pub fn publishers(&self) -> Option<&[Publisher<'a>]> {
+        self.publishers.embedded().map(Vec::as_slice)
+    }
+}
+
+impl<'a> From<GameWithEmbeds<'a>> for GameId<'a> {
+    fn from(value: GameWithEmbeds<'a>) -> Self {
+        value.id
+    }
+}
+
+impl<'a> CategoryWithEmbeds<'a> {
+    /// The embedded game (`CategoryEmbeds::Game`).
+    pub fn game(&self) -> Option<&GameWithEmbeds<'a>> {
+        self.game.as_ref().map(|game| &game.data)
+    }
+
+    /// The embedded variables (`CategoryEmbeds::Variables`).
+    pub fn variables(&self) -> Option<&[Variable<'a>]> {
+        self.variables
+            .as_ref()
+            .map(|variables| variables.data.as_slice())
+    }
+}
+
+impl<'a> From<CategoryWithEmbeds<'a>> for CategoryId<'a> {
+    fn from(value: CategoryWithEmbeds<'a>) -> Self {
+        value.id
+    }
+}
+
+impl<'a> LeaderboardWithEmbeds<'a> {
+    /// The embedded game (`LeaderboardEmbeds::Game`).
+    pub fn game(&self) -> Option<&GameWithEmbeds<'a>> {
+        self.game.embedded()
+    }
+
+    /// The embedded category (`LeaderboardEmbeds::Category`).
+    pub fn category(&self) -> Option<&CategoryWithEmbeds<'a>> {
+        self.category.embedded()
+    }
+
+    /// The embedded level (`LeaderboardEmbeds::Level`). `None` for full-game
+    /// leaderboards.
+    pub fn level(&self) -> Option<&Level<'a>> {
+        self.level.as_ref()?.embedded()?.as_ref()
+    }
+
+    /// The players of all runs on the leaderboard
+    /// (`LeaderboardEmbeds::Players`).
+    pub fn players(&self) -> Option<&[EmbeddedPlayer<'a>]> {
+        self.players.as_ref().map(|players| players.data.as_slice())
+    }
+
+    /// The regions used on the leaderboard (`LeaderboardEmbeds::Regions`).
+    pub fn regions(&self) -> Option<&[Region<'a>]> {
+        self.regions.as_ref().map(|regions| regions.data.as_slice())
+    }
+
+    /// The platforms used on the leaderboard (`LeaderboardEmbeds::Platforms`).
+    pub fn platforms(&self) -> Option<&[Platform<'a>]> {
+        self.platforms
+            .as_ref()
+            .map(|platforms| platforms.data.as_slice())
+    }
+
+    /// The variables applicable to the leaderboard
+    /// (`LeaderboardEmbeds::Variables`).
+    pub fn variables(&self) -> Option<&[Variable<'a>]> {
+        self.variables
+            .as_ref()
+            .map(|variables| variables.data.as_slice())
+    }
+}
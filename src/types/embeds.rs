@@ -0,0 +1,281 @@
+use std::collections::HashMap;
+
+use serde::{de::Error as _, Deserialize, Deserializer};
+use serde_json::Value;
+use url::Url;
+
+use crate::api::{
+    categories::CategoryId, developers::DeveloperId, engines::EngineId, games::GameId,
+    gametypes::GameTypeId, genres::GenreId, levels::LevelId, platforms::PlatformId,
+    publishers::PublisherId, regions::RegionId, runs::RunId, users::UserId, variables::VariableId,
+};
+
+use super::{
+    Assets, CategoryType, Date, DateTime, Developer, Engine, GameType, Genre, Guest, Level, Link,
+    ModeratorRole, Names, Platform, Player, Players, Publisher, RankedRun, Region, Ruleset, Status,
+    System, Times, TimingMethod, User, Variable, Videos,
+};
+
+#[derive(Default, Debug, Clone, PartialEq, Deserialize)]
+pub struct Embedded<T> {
+    pub data: T,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Embeddable<I, T> {
+    Embedded(Embedded<T>),
+    Id(I),
+}
+
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct OptionalEmbed<T>(pub Option<T>);
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+#[serde(tag = "rel")]
+pub enum EmbeddedPlayer<'a> {
+    User(Box<User<'a>>),
+    Guest(Guest),
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct RunWithEmbeds<'a> {
+    pub id: RunId<'a>,
+    pub weblink: Url,
+    pub game: Embeddable<GameId<'a>, GameWithEmbeds<'a>>,
+    #[serde(default)]
+    pub level: Option<Embeddable<LevelId<'a>, OptionalEmbed<Level<'a>>>>,
+    pub category: Embeddable<CategoryId<'a>, CategoryWithEmbeds<'a>>,
+    #[serde(default)]
+    pub videos: Option<Videos>,
+    #[serde(default)]
+    pub comment: Option<String>,
+    pub status: Status<'a>,
+    pub players: Embeddable<Vec<Player<'a>>, Vec<EmbeddedPlayer<'a>>>,
+    #[serde(default, deserialize_with = "super::date_impls::lenient")]
+    pub date: Option<Date>,
+    #[serde(default, deserialize_with = "super::date_impls::lenient")]
+    pub submitted: Option<DateTime>,
+    pub times: Times,
+    pub system: System<'a>,
+    #[serde(default)]
+    pub platform: Option<Embedded<OptionalEmbed<Platform<'a>>>>,
+    #[serde(default)]
+    pub region: Option<Embedded<OptionalEmbed<Region<'a>>>>,
+    #[serde(default)]
+    pub splits: Option<Link>,
+    #[serde(default)]
+    pub values: HashMap<VariableId<'a>, String>,
+    #[serde(default)]
+    pub links: Option<Vec<Link>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct GameWithEmbeds<'a> {
+    pub id: GameId<'a>,
+    pub names: Names,
+    pub abbreviation: String,
+    pub weblink: Url,
+    pub release_date: Date,
+    pub ruleset: Ruleset,
+    pub gametypes: Embeddable<Vec<GameTypeId<'a>>, Vec<GameType<'a>>>,
+    pub platforms: Embeddable<Vec<PlatformId<'a>>, Vec<Platform<'a>>>,
+    pub regions: Embeddable<Vec<RegionId<'a>>, Vec<Region<'a>>>,
+    pub genres: Embeddable<Vec<GenreId<'a>>, Vec<Genre<'a>>>,
+    pub engines: Embeddable<Vec<EngineId<'a>>, Vec<Engine<'a>>>,
+    pub developers: Embeddable<Vec<DeveloperId<'a>>, Vec<Developer<'a>>>,
+    pub publishers: Embeddable<Vec<PublisherId<'a>>, Vec<Publisher<'a>>>,
+    pub moderators: Embeddable<HashMap<UserId<'a>, ModeratorRole>, Vec<User<'a>>>,
+    #[serde(default, deserialize_with = "super::date_impls::lenient")]
+    pub created: Option<DateTime>,
+    pub assets: Assets,
+    #[serde(default)]
+    pub levels: Option<Embedded<Vec<Level<'a>>>>,
+    #[serde(default)]
+    pub categories: Option<Embedded<Vec<CategoryWithEmbeds<'a>>>>,
+    #[serde(default)]
+    pub variables: Option<Embedded<Vec<Variable<'a>>>>,
+    pub links: Vec<Link>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct CategoryWithEmbeds<'a> {
+    pub id: CategoryId<'a>,
+    pub name: String,
+    pub weblink: Url,
+    #[serde(rename = "type")]
+    pub type_field: CategoryType,
+    pub rules: String,
+    pub players: Players,
+    pub miscellaneous: bool,
+    #[serde(default)]
+    pub game: Option<Embedded<GameWithEmbeds<'a>>>,
+    #[serde(default)]
+    pub variables: Option<Embedded<Vec<Variable<'a>>>>,
+    pub links: Vec<Link>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct LeaderboardWithEmbeds<'a> {
+    pub weblink: Url,
+    pub game: Embeddable<GameId<'a>, GameWithEmbeds<'a>>,
+    pub category: Embeddable<CategoryId<'a>, CategoryWithEmbeds<'a>>,
+    #[serde(default)]
+    pub level: Option<Embeddable<LevelId<'a>, OptionalEmbed<Level<'a>>>>,
+    #[serde(default)]
+    pub platform: Option<PlatformId<'a>>,
+    #[serde(default)]
+    pub region: Option<RegionId<'a>>,
+    #[serde(default)]
+    pub emulators: Option<bool>,
+    pub video_only: bool,
+    #[serde(default)]
+    pub timing: Option<TimingMethod>,
+    #[serde(default)]
+    pub values: HashMap<VariableId<'a>, String>,
+    pub runs: Vec<RankedRun<'a>>,
+    #[serde(default)]
+    pub players: Option<Embedded<Vec<EmbeddedPlayer<'a>>>>,
+    #[serde(default)]
+    pub regions: Option<Embedded<Vec<Region<'a>>>>,
+    #[serde(default)]
+    pub platforms: Option<Embedded<Vec<Platform<'a>>>>,
+    #[serde(default)]
+    pub variables: Option<Embedded<Vec<Variable<'a>>>>,
+    pub links: Vec<Link>,
+}
+
+// Embedded resources are wrapped in `{"data": ...}`, anything else is the
+// reference the API returns when the resource was not embedded. This is
+// decided up front so errors point at the embedded resource instead of
+// serde's "did not match any variant of untagged enum".
+impl<'de, I, T> Deserialize<'de> for Embeddable<I, T>
+where
+    I: Deserialize<'de>,
+    T: Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        match Value::deserialize(deserializer)? {
+            Value::Object(mut object) if object.contains_key("data") => {
+                let data = object.remove("data").unwrap_or_default();
+                T::deserialize(data)
+                    .map(|data| Embeddable::Embedded(Embedded { data }))
+                    .map_err(|err| D::Error::custom(format!("invalid embedded resource: {}", err)))
+            }
+            value => I::deserialize(value).map(Embeddable::Id).map_err(|err| {
+                D::Error::custom(format!(
+                    "expected a reference or an embedded resource: {}",
+                    err
+                ))
+            }),
+        }
+    }
+}
+
+// Missing resources (e.g. the level of a full-game run) are embedded as an
+// empty list.
+impl<'de, T> Deserialize<'de> for OptionalEmbed<T>
+where
+    T: Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Data<T> {
+            Some(T),
+            None([(); 0]),
+        }
+
+        match Data::deserialize(deserializer)? {
+            Data::Some(value) => Ok(Self(Some(value))),
+            Data::None(_) => Ok(Self(None)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn level() -> Value {
+        json!({
+            "id": "wkpqmw8d",
+            "name": "Bob-omb Battlefield",
+            "weblink": "https://www.speedrun.com/sm64/Bob-omb_Battlefield",
+            "links": [],
+        })
+    }
+
+    #[test]
+    fn embeddable_reference() {
+        let level: Embeddable<LevelId<'_>, Level<'_>> =
+            serde_json::from_value(json!("wkpqmw8d")).unwrap();
+        assert_eq!(level.id(), Some(&LevelId::new("wkpqmw8d")));
+        assert!(level.embedded().is_none());
+    }
+
+    #[test]
+    fn embeddable_resource() {
+        let level: Embeddable<LevelId<'_>, Level<'_>> =
+            serde_json::from_value(json!({ "data": level() })).unwrap();
+        assert_eq!(level.embedded().unwrap().name, "Bob-omb Battlefield");
+        assert!(level.id().is_none());
+    }
+
+    #[test]
+    fn embeddable_errors_name_the_failure() {
+        let err = serde_json::from_value::<Embeddable<LevelId<'_>, Level<'_>>>(
+            json!({ "data": { "id": "wkpqmw8d" } }),
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("invalid embedded resource"));
+        assert!(err.to_string().contains("missing field `name`"));
+
+        let err =
+            serde_json::from_value::<Embeddable<LevelId<'_>, Level<'_>>>(json!(1)).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("expected a reference or an embedded resource"));
+    }
+
+    #[test]
+    fn missing_embed_is_an_empty_list() {
+        let missing: Embeddable<LevelId<'_>, OptionalEmbed<Level<'_>>> =
+            serde_json::from_value(json!({ "data": [] })).unwrap();
+        assert_eq!(missing.embedded(), Some(&OptionalEmbed(None)));
+
+        let present: Embeddable<LevelId<'_>, OptionalEmbed<Level<'_>>> =
+            serde_json::from_value(json!({ "data": level() })).unwrap();
+        assert!(present.embedded().unwrap().as_ref().is_some());
+    }
+
+    #[test]
+    fn category_without_embeds() {
+        let category: CategoryWithEmbeds<'_> = serde_json::from_value(json!({
+            "id": "wkpoo02r",
+            "name": "120 Star",
+            "weblink": "https://www.speedrun.com/sm64#120_Star",
+            "type": "per-game",
+            "rules": "",
+            "players": { "type": "exactly", "value": 1 },
+            "miscellaneous": false,
+            "links": [],
+        }))
+        .unwrap();
+        assert!(category.game().is_none());
+        assert!(category.variables().is_none());
+        assert_eq!(CategoryId::from(category), CategoryId::new("wkpoo02r"));
+    }
+}
@@ -2,7 +2,7 @@ use std::fmt::Display;
 
 use crate::api::games::GameId;
 
-use super::Game;
+use super::{BulkGame, Game};
 
 impl<'a> From<Game<'a>> for GameId<'a> {
     fn from(value: Game<'a>) -> Self {
@@ -15,3 +15,15 @@ impl Display for Game<'_> {
         write!(f, "{}", &self.names.international)
     }
 }
+
+impl<'a> From<BulkGame<'a>> for GameId<'a> {
+    fn from(value: BulkGame<'a>) -> Self {
+        value.id
+    }
+}
+
+impl Display for BulkGame<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", &self.names.international)
+    }
+}
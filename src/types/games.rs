@@ -1,6 +1,7 @@
 use std::collections::HashMap;
 
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use url::Url;
 
 use crate::api::{
     developers::DeveloperId, engines::EngineId, games::GameId, gametypes::GameTypeId,
@@ -8,7 +9,7 @@ use crate::api::{
     users::UserId,
 };
 
-use super::{Assets, Link, ModeratorRole, Names, TimingMethod};
+use super::{Assets, Date, DateTime, Link, ModeratorRole, Names, TimingMethod};
 
 #[derive(Debug, Clone, PartialEq, Deserialize)]
 #[serde(rename_all = "kebab-case")]
@@ -16,8 +17,8 @@ pub struct Game<'a> {
     pub id: GameId<'a>,
     pub names: Names,
     pub abbreviation: String,
-    pub weblink: String,
-    pub release_date: String,
+    pub weblink: Url,
+    pub release_date: Date,
     pub ruleset: Ruleset,
     pub gametypes: Vec<GameTypeId<'a>>,
     pub platforms: Vec<PlatformId<'a>>,
@@ -27,11 +28,21 @@ pub struct Game<'a> {
     pub developers: Vec<DeveloperId<'a>>,
     pub publishers: Vec<PublisherId<'a>>,
     pub moderators: HashMap<UserId<'a>, ModeratorRole>,
-    pub created: Option<String>,
+    #[serde(default, deserialize_with = "super::date_impls::lenient")]
+    pub created: Option<DateTime>,
     pub assets: Assets,
     pub links: Vec<Link>,
 }
 
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct BulkGame<'a> {
+    pub id: GameId<'a>,
+    pub names: Names,
+    pub abbreviation: String,
+    pub weblink: Url,
+}
+
 #[derive(Debug, Clone, PartialEq, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct Ruleset {
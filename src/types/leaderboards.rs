@@ -1,6 +1,7 @@
 use std::collections::HashMap;
 
 use serde::Deserialize;
+use url::Url;
 
 use crate::api::{
     categories::CategoryId, games::GameId, levels::LevelId, platforms::PlatformId,
@@ -12,7 +13,7 @@ use super::{common::TimingMethod, Link, Run};
 #[derive(Debug, Clone, PartialEq, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct Leaderboard<'a> {
-    pub weblink: String,
+    pub weblink: Url,
     pub game: GameId<'a>,
     pub category: CategoryId<'a>,
     #[serde(default)]
@@ -1,12 +1,13 @@
 use crate::{api::levels::LevelId, types::Link};
 use serde::Deserialize;
+use url::Url;
 
 #[derive(Debug, Clone, PartialEq, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Level<'a> {
     pub id: LevelId<'a>,
     pub name: String,
-    pub weblink: String,
+    pub weblink: Url,
     #[serde(default)]
     pub rules: Option<String>,
     pub links: Vec<Link>,
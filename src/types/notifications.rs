@@ -1,12 +1,13 @@
 use serde::Deserialize;
+use url::Url;
 
-use super::Link;
+use super::{DateTime, Link};
 
 #[derive(Debug, Clone, PartialEq, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct Notification {
     pub id: String,
-    pub created: String,
+    pub created: DateTime,
     pub status: ReadStatus,
     pub text: String,
     pub item: Item,
@@ -17,10 +18,10 @@ pub struct Notification {
 #[serde(rename_all = "kebab-case")]
 #[serde(tag = "rel", content = "uri")]
 pub enum Item {
-    Post(String),
-    Run(String),
-    Game(String),
-    Guide(String),
+    Post(Url),
+    Run(Url),
+    Game(Url),
+    Guide(Url),
 }
 
 #[derive(Debug, Clone, PartialEq, Deserialize)]
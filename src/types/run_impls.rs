@@ -1,6 +1,61 @@
-use crate::api::{categories::CategoryId, games::GameId, runs::RunId};
+use std::fmt::Display;
 
-use super::Run;
+use serde::{Deserialize, Deserializer};
+use url::Url;
+
+use crate::api::{
+    categories::CategoryId,
+    games::GameId,
+    leaderboards::{FullGameLeaderboard, IndividualLevelLeaderboard, Leaderboard},
+    runs::RunId,
+    variables::VariableId,
+};
+
+use super::{Run, RunTime, Times, TimingMethod, VideoUri};
+
+impl<'a> Run<'a> {
+    /// The leaderboard this run was submitted to.
+    ///
+    /// The run's values for the variables in `subcategories` are used to
+    /// select the leaderboard. A run does not say which of its variables are
+    /// subcategories, so these have to be given by the caller (see
+    /// [`Variable::is_subcategory`](super::Variable::is_subcategory)).
+    pub fn leaderboard(&self, subcategories: &[VariableId<'_>]) -> Leaderboard<'a> {
+        let variables = self
+            .values
+            .iter()
+            .filter(|(variable, _)| subcategories.iter().any(|sub| sub == *variable))
+            .map(|(variable, value)| (variable.clone(), value.clone()));
+        match &self.level {
+            Some(level) => IndividualLevelLeaderboard::builder()
+                .game(self.game.clone())
+                .level(level.clone())
+                .category(self.category.clone())
+                .variables(variables)
+                .build()
+                .expect("all required fields are set")
+                .into(),
+            None => FullGameLeaderboard::builder()
+                .game(self.game.clone())
+                .category(self.category.clone())
+                .variables(variables)
+                .build()
+                .expect("all required fields are set")
+                .into(),
+        }
+    }
+}
+
+impl Times {
+    /// The time for the given timing method, if the run has one.
+    pub fn get(&self, method: TimingMethod) -> Option<RunTime> {
+        match method {
+            TimingMethod::Realtime => self.realtime,
+            TimingMethod::RealtimeNoloads => self.realtime_noloads,
+            TimingMethod::Ingame => self.ingame,
+        }
+    }
+}
 
 impl<'a> From<Run<'a>> for RunId<'a> {
     fn from(value: Run<'a>) -> Self {
@@ -19,3 +74,126 @@ impl<'a> From<Run<'a>> for CategoryId<'a> {
         value.category
     }
 }
+
+impl VideoUri {
+    /// The URL of the video, if the link is a web URL.
+    pub fn url(&self) -> Option<&Url> {
+        match self {
+            VideoUri::Url(url) => Some(url),
+            VideoUri::Raw(_) => None,
+        }
+    }
+
+    /// The link as a string.
+    pub fn as_str(&self) -> &str {
+        match self {
+            VideoUri::Url(url) => url.as_str(),
+            VideoUri::Raw(raw) => raw,
+        }
+    }
+
+    // Links are sometimes missing the scheme (`youtu.be/...`), those are
+    // assumed to use `https`. Only hosts with a domain are accepted, so that
+    // text like `N/A` is not turned into `https://n/A`.
+    fn parse(value: &str) -> Self {
+        let value = value.trim();
+        let url = match Url::parse(value) {
+            Ok(url) => Some(url),
+            Err(url::ParseError::RelativeUrlWithoutBase) => {
+                Url::parse(&format!("https://{}", value)).ok()
+            }
+            Err(_) => None,
+        };
+        match url {
+            Some(url) if is_web_url(&url) => VideoUri::Url(url),
+            _ => VideoUri::Raw(value.to_owned()),
+        }
+    }
+}
+
+fn is_web_url(url: &Url) -> bool {
+    matches!(url.scheme(), "http" | "https")
+        && url
+            .host_str()
+            .is_some_and(|host| host.contains('.') && !host.ends_with('.'))
+}
+
+impl Display for VideoUri {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for VideoUri {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = String::deserialize(deserializer)?;
+        Ok(VideoUri::parse(&value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn video(value: &str) -> VideoUri {
+        serde_json::from_value(serde_json::Value::String(value.into())).unwrap()
+    }
+
+    #[test]
+    fn video_urls() {
+        let uri = video("https://www.youtube.com/watch?v=dQw4w9WgXcQ");
+        assert_eq!(
+            uri.url().map(Url::as_str),
+            Some("https://www.youtube.com/watch?v=dQw4w9WgXcQ")
+        );
+
+        let uri = video(" youtu.be/dQw4w9WgXcQ ");
+        assert_eq!(
+            uri.url().map(Url::as_str),
+            Some("https://youtu.be/dQw4w9WgXcQ")
+        );
+    }
+
+    #[test]
+    fn video_text_is_kept() {
+        for text in &[
+            "N/A",
+            "see comment",
+            "localhost",
+            "ftp://example.com/run.mp4",
+            "",
+        ] {
+            let uri = video(text);
+            assert_eq!(uri, VideoUri::Raw(text.trim().to_owned()), "{}", text);
+            assert!(uri.url().is_none());
+            assert_eq!(uri.to_string(), *text);
+        }
+    }
+
+    #[test]
+    fn times_keep_durations_and_seconds() {
+        let times: Times = serde_json::from_value(serde_json::json!({
+            "primary": "PT1M2.5S",
+            "primary_t": 62.5,
+            "realtime": "PT1M2.5S",
+            "realtime_t": 62.5,
+            "realtime_noloads": null,
+            "realtime_noloads_t": 0,
+            "ingame": "PT58S",
+            "ingame_t": 58,
+        }))
+        .unwrap();
+        assert_eq!(times.primary, RunTime::from_millis(62_500));
+        assert_eq!(times.primary_t, 62.5);
+        assert_eq!(
+            times.get(TimingMethod::Ingame),
+            Some(RunTime::from_millis(58_000))
+        );
+        assert_eq!(times.ingame_t, 58.0);
+        assert_eq!(times.get(TimingMethod::RealtimeNoloads), None);
+        assert_eq!(times.realtime_noloads_t, 0.0);
+    }
+}
@@ -0,0 +1,21 @@
+use thiserror::Error;
+
+/// The time of a run, with millisecond precision.
+///
+/// Parsed from and formatted as the ISO 8601 durations used by the API
+/// (`PT1H2M3.45S`), and displayed the way speedrun.com shows times
+/// (`1h 02m 03s 450ms`). Times can be negative, e.g. when computing the
+/// difference between two runs.
+///
+/// Arithmetic saturates instead of overflowing; use
+/// [`checked_add`](Self::checked_add) and [`checked_sub`](Self::checked_sub)
+/// to detect overflow.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct RunTime {
+    pub(super) millis: i64,
+}
+
+/// Error returned when parsing an invalid ISO 8601 duration.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("invalid run time `{0}`")]
+pub struct RunTimeError(pub(super) String);
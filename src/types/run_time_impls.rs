@@ -0,0 +1,462 @@
+use std::{
+    convert::TryFrom,
+    fmt::{self, Display},
+    iter::Sum,
+    ops::{Add, AddAssign, Neg, Sub, SubAssign},
+    str::FromStr,
+    time::Duration,
+};
+
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+use super::{RunTime, RunTimeError};
+
+const MILLIS_PER_SECOND: i64 = 1000;
+const MILLIS_PER_MINUTE: i64 = 60 * MILLIS_PER_SECOND;
+const MILLIS_PER_HOUR: i64 = 60 * MILLIS_PER_MINUTE;
+const MILLIS_PER_DAY: i64 = 24 * MILLIS_PER_HOUR;
+
+impl RunTime {
+    /// A time of zero.
+    pub const ZERO: RunTime = RunTime { millis: 0 };
+
+    /// Create a time from a number of milliseconds.
+    pub const fn from_millis(millis: i64) -> Self {
+        Self { millis }
+    }
+
+    /// Create a time from a number of seconds, rounded to the nearest
+    /// millisecond.
+    pub fn from_secs_f64(secs: f64) -> Self {
+        Self {
+            millis: (secs * 1000.0).round() as i64,
+        }
+    }
+
+    /// The time in milliseconds.
+    pub const fn as_millis(&self) -> i64 {
+        self.millis
+    }
+
+    /// The time in seconds.
+    pub fn as_secs_f64(&self) -> f64 {
+        self.millis as f64 / 1000.0
+    }
+
+    /// The absolute value of this time, saturating at the largest
+    /// representable time.
+    pub const fn abs(&self) -> Self {
+        Self {
+            millis: self.millis.saturating_abs(),
+        }
+    }
+
+    /// Add two times, returning `None` on overflow.
+    pub const fn checked_add(self, rhs: Self) -> Option<Self> {
+        match self.millis.checked_add(rhs.millis) {
+            Some(millis) => Some(Self { millis }),
+            None => None,
+        }
+    }
+
+    /// Subtract two times, returning `None` on overflow.
+    pub const fn checked_sub(self, rhs: Self) -> Option<Self> {
+        match self.millis.checked_sub(rhs.millis) {
+            Some(millis) => Some(Self { millis }),
+            None => None,
+        }
+    }
+
+    /// This time with the milliseconds dropped, as shown on leaderboards that
+    /// hide milliseconds.
+    pub const fn truncate_to_secs(&self) -> Self {
+        Self {
+            millis: self.millis / MILLIS_PER_SECOND * MILLIS_PER_SECOND,
+        }
+    }
+
+    /// Returns `true` if the time is less than zero.
+    pub const fn is_negative(&self) -> bool {
+        self.millis < 0
+    }
+
+    /// Convert into a [`Duration`]. Returns `None` for negative times.
+    pub fn to_duration(&self) -> Option<Duration> {
+        u64::try_from(self.millis).ok().map(Duration::from_millis)
+    }
+
+    /// Format as an ISO 8601 duration, as used by the API (`PT1H2M3.45S`).
+    pub fn to_iso8601(&self) -> String {
+        let sign = if self.is_negative() { "-" } else { "" };
+        let (hours, minutes, seconds, millis) = self.abs().parts();
+        let mut out = format!("{}PT", sign);
+        if hours > 0 {
+            out.push_str(&format!("{}H", hours));
+        }
+        if minutes > 0 {
+            out.push_str(&format!("{}M", minutes));
+        }
+        if millis > 0 {
+            let fraction = format!("{:03}", millis);
+            out.push_str(&format!("{}.{}S", seconds, fraction.trim_end_matches('0')));
+        } else if seconds > 0 || (hours == 0 && minutes == 0) {
+            out.push_str(&format!("{}S", seconds));
+        }
+        out
+    }
+
+    /// Split a non-negative time into hours, minutes, seconds and
+    /// milliseconds.
+    fn parts(&self) -> (i64, i64, i64, i64) {
+        let millis = self.millis;
+        (
+            millis / MILLIS_PER_HOUR,
+            millis % MILLIS_PER_HOUR / MILLIS_PER_MINUTE,
+            millis % MILLIS_PER_MINUTE / MILLIS_PER_SECOND,
+            millis % MILLIS_PER_SECOND,
+        )
+    }
+}
+
+fn parse_iso8601(s: &str) -> Option<RunTime> {
+    let (negative, rest) = match s.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, s),
+    };
+    let rest = rest.strip_prefix('P')?;
+    let (days, time) = match rest.find('T') {
+        Some(index) => (&rest[..index], Some(&rest[index + 1..])),
+        None => (rest, None),
+    };
+
+    let mut millis = 0i64;
+    if !days.is_empty() {
+        let days = days.strip_suffix('D')?;
+        millis = millis.checked_add(integer(days)?.checked_mul(MILLIS_PER_DAY)?)?;
+    }
+    if let Some(mut time) = time {
+        if time.is_empty() {
+            return None;
+        }
+        for (unit, scale) in [
+            ('H', MILLIS_PER_HOUR),
+            ('M', MILLIS_PER_MINUTE),
+            ('S', MILLIS_PER_SECOND),
+        ] {
+            if let Some(index) = time.find(unit) {
+                let value = if unit == 'S' {
+                    seconds(&time[..index])?
+                } else {
+                    integer(&time[..index])?.checked_mul(scale)?
+                };
+                millis = millis.checked_add(value)?;
+                time = &time[index + 1..];
+            }
+        }
+        if !time.is_empty() {
+            return None;
+        }
+    } else if days.is_empty() {
+        return None;
+    }
+
+    Some(RunTime::from_millis(if negative {
+        -millis
+    } else {
+        millis
+    }))
+}
+
+fn integer(s: &str) -> Option<i64> {
+    if s.is_empty() || !s.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    s.parse().ok()
+}
+
+/// Parse seconds with an optional fraction into milliseconds, rounding to the
+/// nearest millisecond.
+fn seconds(s: &str) -> Option<i64> {
+    let (whole, fraction) = match s.find(['.', ',']) {
+        Some(index) => (&s[..index], &s[index + 1..]),
+        None => (s, ""),
+    };
+    let mut millis = integer(whole)?.checked_mul(MILLIS_PER_SECOND)?;
+    if !fraction.is_empty() {
+        if !fraction.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        let digits: Vec<i64> = fraction.bytes().map(|b| i64::from(b - b'0')).collect();
+        let mut fraction_millis = digits
+            .iter()
+            .chain([0, 0, 0].iter())
+            .take(3)
+            .fold(0, |acc, d| acc * 10 + d);
+        if digits.get(3).is_some_and(|digit| *digit >= 5) {
+            fraction_millis += 1;
+        }
+        millis = millis.checked_add(fraction_millis)?;
+    }
+    Some(millis)
+}
+
+impl FromStr for RunTime {
+    type Err = RunTimeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_iso8601(s.trim()).ok_or_else(|| RunTimeError(s.into()))
+    }
+}
+
+impl Display for RunTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_negative() {
+            f.write_str("-")?;
+        }
+        let (hours, minutes, seconds, millis) = self.abs().parts();
+        if hours > 0 {
+            write!(f, "{}h {:02}m {:02}s", hours, minutes, seconds)?;
+        } else if minutes > 0 {
+            write!(f, "{}m {:02}s", minutes, seconds)?;
+        } else {
+            write!(f, "{}s", seconds)?;
+        }
+        if millis > 0 {
+            write!(f, " {:03}ms", millis)?;
+        }
+        Ok(())
+    }
+}
+
+impl From<Duration> for RunTime {
+    fn from(value: Duration) -> Self {
+        Self::from_millis(i64::try_from(value.as_millis()).unwrap_or(i64::MAX))
+    }
+}
+
+impl Add for RunTime {
+    type Output = RunTime;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self::from_millis(self.millis.saturating_add(rhs.millis))
+    }
+}
+
+impl Sub for RunTime {
+    type Output = RunTime;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self::from_millis(self.millis.saturating_sub(rhs.millis))
+    }
+}
+
+impl AddAssign for RunTime {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl SubAssign for RunTime {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl Neg for RunTime {
+    type Output = RunTime;
+
+    fn neg(self) -> Self::Output {
+        Self::from_millis(self.millis.saturating_neg())
+    }
+}
+
+impl Sum for RunTime {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(RunTime::ZERO, Add::add)
+    }
+}
+
+impl<'a> Sum<&'a RunTime> for RunTime {
+    fn sum<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.copied().sum()
+    }
+}
+
+impl Serialize for RunTime {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.to_iso8601())
+    }
+}
+
+/// Deserializes from an ISO 8601 duration, or a number of seconds.
+impl<'de> Deserialize<'de> for RunTime {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct Visitor;
+
+        impl de::Visitor<'_> for Visitor {
+            type Value = RunTime;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("an ISO 8601 duration or a number of seconds")
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+                v.parse().map_err(E::custom)
+            }
+
+            fn visit_f64<E: de::Error>(self, v: f64) -> Result<Self::Value, E> {
+                Ok(RunTime::from_secs_f64(v))
+            }
+
+            fn visit_i64<E: de::Error>(self, v: i64) -> Result<Self::Value, E> {
+                v.checked_mul(MILLIS_PER_SECOND)
+                    .map(RunTime::from_millis)
+                    .ok_or_else(|| E::custom("run time out of range"))
+            }
+
+            fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+                i64::try_from(v)
+                    .map_err(E::custom)
+                    .and_then(|v| self.visit_i64(v))
+            }
+        }
+
+        deserializer.deserialize_any(Visitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn time(s: &str) -> RunTime {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn parse_iso8601_durations() {
+        assert_eq!(time("PT1H2M3.45S").as_millis(), 3_723_450);
+        assert_eq!(time("PT59S").as_millis(), 59_000);
+        assert_eq!(time("PT2M").as_millis(), 120_000);
+        assert_eq!(time("P1DT1S").as_millis(), MILLIS_PER_DAY + 1000);
+        assert_eq!(time("P1D").as_millis(), MILLIS_PER_DAY);
+        assert_eq!(time("PT1,5S").as_millis(), 1500);
+        assert_eq!(time("-PT1S").as_millis(), -1000);
+        assert_eq!(time(" PT1S ").as_millis(), 1000);
+    }
+
+    #[test]
+    fn fractions_round_to_millis() {
+        assert_eq!(time("PT0.0004S").as_millis(), 0);
+        assert_eq!(time("PT0.0005S").as_millis(), 1);
+        assert_eq!(time("PT1.9996S").as_millis(), 2000);
+    }
+
+    #[test]
+    fn invalid_durations() {
+        for invalid in &[
+            "",
+            "P",
+            "PT",
+            "1H",
+            "PT1",
+            "PT1X",
+            "PTH",
+            "PT1S1M",
+            "PT-1S",
+            "PT1.S2",
+            "PT99999999999999999H",
+        ] {
+            let err = invalid.parse::<RunTime>().unwrap_err();
+            assert_eq!(err.to_string(), format!("invalid run time `{}`", invalid));
+        }
+    }
+
+    #[test]
+    fn format_iso8601() {
+        for iso in &["PT1H2M3.45S", "PT0S", "PT1H", "PT1H3S", "PT0.001S", "-PT1M"] {
+            assert_eq!(time(iso).to_iso8601(), *iso);
+        }
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(time("PT1H2M3.45S").to_string(), "1h 02m 03s 450ms");
+        assert_eq!(time("PT2M3S").to_string(), "2m 03s");
+        assert_eq!(time("PT3.007S").to_string(), "3s 007ms");
+        assert_eq!(time("-PT1M").to_string(), "-1m 00s");
+        assert_eq!(RunTime::ZERO.to_string(), "0s");
+    }
+
+    #[test]
+    fn arithmetic() {
+        let a = time("PT1M");
+        let b = time("PT1.5S");
+        assert_eq!((a + b).as_millis(), 61_500);
+        assert_eq!((b - a).as_millis(), -58_500);
+        assert_eq!((b - a).abs().as_millis(), 58_500);
+        assert_eq!(-a, time("-PT1M"));
+        assert_eq!([a, b, b].iter().sum::<RunTime>().as_millis(), 63_000);
+        assert_eq!(a.truncate_to_secs(), a);
+        assert_eq!(b.truncate_to_secs().as_millis(), 1000);
+
+        let mut c = a;
+        c += b;
+        c -= a;
+        assert_eq!(c, b);
+    }
+
+    #[test]
+    fn arithmetic_saturates() {
+        let max = RunTime::from_millis(i64::MAX);
+        let min = RunTime::from_millis(i64::MIN);
+        let one = RunTime::from_millis(1);
+
+        assert_eq!(max + one, max);
+        assert_eq!(min - one, min);
+        assert_eq!(-min, max);
+        assert_eq!(min.abs(), max);
+        assert_eq!([max, max].iter().sum::<RunTime>(), max);
+
+        assert_eq!(max.checked_add(one), None);
+        assert_eq!(min.checked_sub(one), None);
+        assert_eq!(one.checked_add(one), Some(RunTime::from_millis(2)));
+        assert_eq!(one.checked_sub(one), Some(RunTime::ZERO));
+    }
+
+    #[test]
+    fn conversions() {
+        assert_eq!(RunTime::from_secs_f64(1.2345).as_millis(), 1235);
+        assert_eq!(time("PT1.5S").as_secs_f64(), 1.5);
+        assert_eq!(
+            time("PT1.5S").to_duration(),
+            Some(Duration::from_millis(1500))
+        );
+        assert_eq!(time("-PT1S").to_duration(), None);
+        assert_eq!(
+            RunTime::from(Duration::from_secs(u64::MAX)).as_millis(),
+            i64::MAX
+        );
+    }
+
+    #[test]
+    fn serde() {
+        let parsed: RunTime = serde_json::from_str("\"PT1M2.5S\"").unwrap();
+        assert_eq!(parsed.as_millis(), 62_500);
+        assert_eq!(serde_json::to_string(&parsed).unwrap(), "\"PT1M2.5S\"");
+
+        let parsed: RunTime = serde_json::from_str("62.5").unwrap();
+        assert_eq!(parsed.as_millis(), 62_500);
+        let parsed: RunTime = serde_json::from_str("62").unwrap();
+        assert_eq!(parsed.as_millis(), 62_000);
+
+        assert!(serde_json::from_str::<RunTime>("\"1:02\"").is_err());
+        assert!(serde_json::from_str::<RunTime>(&u64::MAX.to_string()).is_err());
+    }
+}
@@ -1,19 +1,20 @@
 use std::collections::HashMap;
 
 use serde::Deserialize;
+use url::Url;
 
 use crate::api::{
     categories::CategoryId, games::GameId, levels::LevelId, platforms::PlatformId,
     regions::RegionId, runs::RunId, users::UserId, variables::VariableId,
 };
 
-use super::Link;
+use super::{Date, DateTime, Link, RunTime};
 
 #[derive(Debug, Clone, PartialEq, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct Run<'a> {
     pub id: RunId<'a>,
-    pub weblink: String,
+    pub weblink: Url,
     pub game: GameId<'a>,
     #[serde(default)]
     pub level: Option<LevelId<'a>>,
@@ -24,10 +25,10 @@ pub struct Run<'a> {
     pub comment: Option<String>,
     pub status: Status<'a>,
     pub players: Vec<Player<'a>>,
-    #[serde(default)]
-    pub date: Option<String>,
-    #[serde(default)]
-    pub submitted: Option<String>,
+    #[serde(default, deserialize_with = "super::date_impls::lenient")]
+    pub date: Option<Date>,
+    #[serde(default, deserialize_with = "super::date_impls::lenient")]
+    pub submitted: Option<DateTime>,
     pub times: Times,
     pub system: System<'a>,
     #[serde(default)]
@@ -46,10 +47,22 @@ pub struct Videos {
     pub links: Vec<VideoLink>,
 }
 
-#[derive(Default, Debug, Clone, PartialEq, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct VideoLink {
-    pub uri: String,
+    pub uri: VideoUri,
+}
+
+/// A video link, as entered by the runner.
+///
+/// Links are not validated by speedrun.com, so anything that is not a usable
+/// URL (e.g. `N/A`) is kept as it was entered.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VideoUri {
+    /// A web URL.
+    Url(Url),
+    /// Text that could not be read as a web URL.
+    Raw(String),
 }
 
 #[derive(Debug, Clone, PartialEq, Deserialize)]
@@ -60,7 +73,8 @@ pub enum Status<'a> {
     #[serde(rename_all = "kebab-case")]
     Verified {
         examiner: Option<UserId<'a>>,
-        verify_date: Option<String>,
+        #[serde(default, deserialize_with = "super::date_impls::lenient")]
+        verify_date: Option<DateTime>,
     },
     Rejected {
         examiner: UserId<'a>,
@@ -72,20 +86,20 @@ pub enum Status<'a> {
 #[serde(rename_all = "kebab-case")]
 #[serde(tag = "rel")]
 pub enum Player<'a> {
-    User { id: UserId<'a>, uri: String },
-    Guest { name: String, uri: String },
+    User { id: UserId<'a>, uri: Url },
+    Guest { name: String, uri: Url },
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub struct Times {
-    pub primary: String,
+    pub primary: RunTime,
     pub primary_t: f64,
-    pub realtime: Option<String>,
+    pub realtime: Option<RunTime>,
     pub realtime_t: f64,
-    pub realtime_noloads: Option<String>,
+    pub realtime_noloads: Option<RunTime>,
     pub realtime_noloads_t: f64,
-    pub ingame: Option<String>,
+    pub ingame: Option<RunTime>,
     pub ingame_t: f64,
 }
 
@@ -1,10 +1,11 @@
 use std::collections::HashMap;
 
 use serde::Deserialize;
+use url::Url;
 
 use crate::api::{series::SeriesId, users::UserId};
 
-use super::{Assets, Link, ModeratorRole, Names};
+use super::{Assets, DateTime, Link, ModeratorRole, Names};
 
 #[derive(Debug, Clone, PartialEq, Deserialize)]
 #[serde(rename_all = "kebab-case")]
@@ -12,11 +13,11 @@ pub struct Series<'a> {
     pub id: SeriesId<'a>,
     pub names: Names,
     pub abbreviation: String,
-    pub weblink: String,
+    pub weblink: Url,
     #[serde(default)]
     pub moderators: HashMap<UserId<'a>, ModeratorRole>,
-    #[serde(default)]
-    pub created: Option<String>,
+    #[serde(default, deserialize_with = "super::date_impls::lenient")]
+    pub created: Option<DateTime>,
     pub assets: Assets,
     pub links: Vec<Link>,
 }
@@ -1,8 +1,9 @@
 use serde::Deserialize;
+use url::Url;
 
 use crate::api::users::UserId;
 
-use super::{Link, Names};
+use super::{DateTime, Link, Names};
 
 #[derive(Debug, Clone, PartialEq, Deserialize)]
 #[serde(rename_all = "kebab-case")]
@@ -10,10 +11,11 @@ pub struct User<'a> {
     pub id: UserId<'a>,
     pub names: Names,
     pub pronouns: Option<String>,
-    pub weblink: String,
+    pub weblink: Url,
     pub name_style: NameStyle,
     pub role: UserRole,
-    pub signup: Option<String>,
+    #[serde(default, deserialize_with = "super::date_impls::lenient")]
+    pub signup: Option<DateTime>,
     pub location: Option<Location>,
     pub twitch: Option<BasicLink>,
     pub hitbox: Option<BasicLink>,
@@ -69,8 +71,8 @@ pub struct Place {
     pub names: Names,
 }
 
-#[derive(Default, Debug, Clone, PartialEq, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct BasicLink {
-    pub uri: String,
+    pub uri: Url,
 }
@@ -1,8 +1,28 @@
 use std::fmt::Display;
 
-use crate::api::variables::VariableId;
+use crate::api::{categories::CategoryId, levels::LevelId, variables::VariableId};
 
-use super::{Value, Variable};
+use super::{Scope, Value, Variable};
+
+impl Variable<'_> {
+    /// Returns `true` if the variable applies to runs of `category`, for the
+    /// given `level` (`None` for full-game runs).
+    pub fn applies_to(&self, category: &CategoryId<'_>, level: Option<&LevelId<'_>>) -> bool {
+        let category_matches = self
+            .category
+            .as_ref()
+            .is_none_or(|variable_category| variable_category == category);
+        let scope_matches = match &self.scope {
+            Scope::Global => true,
+            Scope::FullGame => level.is_none(),
+            Scope::AllLevels => level.is_some(),
+            Scope::SingleLevel { level: scope_level } => {
+                level.is_some_and(|level| level.to_string() == *scope_level)
+            }
+        };
+        category_matches && scope_matches
+    }
+}
 
 impl<'a> From<Variable<'a>> for VariableId<'a> {
     fn from(value: Variable<'a>) -> Self {
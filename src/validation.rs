@@ -0,0 +1,493 @@
+//! Validation of run submissions.
+//!
+//! [`RunValidator`] checks a [`CreateRun`] against the rules of a game and
+//! category before it is submitted, so that submissions the API would reject
+//! can be reported up front.
+//!
+//! # Example
+//!
+//! ```rust ,no_run
+//! use speedrun_api::{
+//!     api::runs::CreateRun, types::RunTime, validation::RunValidator, SpeedrunApiBuilder,
+//! };
+//!
+//! # fn main() -> speedrun_api::error::SpeedrunApiResult<()> {
+//! let client = SpeedrunApiBuilder::new().api_key("my api key").build()?;
+//! let validator = RunValidator::load(&client, "v1pxjz68", "n2y3r8do")?;
+//!
+//! let run = CreateRun::builder()
+//!     .category("n2y3r8do")
+//!     .realtime(RunTime::from_millis(4_567_890))
+//!     .emulated(false)
+//!     .build()
+//!     .unwrap();
+//! if let Err(errors) = validator.validate(&run) {
+//!     for error in errors {
+//!         println!("{}", error);
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+use thiserror::Error;
+
+use crate::{
+    api::{
+        categories::{Category, CategoryId, CategoryVariables},
+        games::{Game, GameId},
+        platforms::PlatformId,
+        regions::RegionId,
+        runs::{CreateRun, ValueType},
+        variables::{ValueId, VariableId},
+        ApiError, AsyncClient, AsyncQuery, Client, Query,
+    },
+    types::{self, CategoryType, Players, TimingMethod, Variable},
+};
+
+/// A rule that a run submission does not follow.
+#[derive(Debug, Clone, PartialEq, Error)]
+#[non_exhaustive]
+pub enum ValidationError {
+    /// The run was submitted to a different category.
+    #[error("run is for category {0}, but the rules are for another category")]
+    WrongCategory(CategoryId<'static>),
+    /// The category is an individual-level category, but no level was set.
+    #[error("a level is required for individual-level categories")]
+    MissingLevel,
+    /// The category is a full-game category, but a level was set.
+    #[error("a level cannot be set for full-game categories")]
+    UnexpectedLevel,
+    /// The game requires a video.
+    #[error("a video is required")]
+    MissingVideo,
+    /// No time was set for any of the timing methods of the game.
+    #[error("a time is required for one of the timing methods {0:?}")]
+    MissingTime(Vec<TimingMethod>),
+    /// A time was set for a timing method that the game does not use.
+    #[error("the game does not use timing method {0:?}")]
+    TimingMethodNotAllowed(TimingMethod),
+    /// The run was done on an emulator, but the game does not allow them.
+    #[error("emulators are not allowed")]
+    EmulatorNotAllowed,
+    /// The game requires a platform.
+    #[error("a platform is required")]
+    MissingPlatform,
+    /// The platform is not one of the platforms of the game.
+    #[error("platform {0} is not available for this game")]
+    InvalidPlatform(PlatformId<'static>),
+    /// The game requires a region.
+    #[error("a region is required")]
+    MissingRegion,
+    /// The region is not one of the regions of the game.
+    #[error("region {0} is not available for this game")]
+    InvalidRegion(RegionId<'static>),
+    /// The category requires an exact number of players.
+    #[error("the category requires exactly {expected} players, got {actual}")]
+    WrongPlayerCount {
+        /// The number of players required.
+        expected: i64,
+        /// The number of players in the run.
+        actual: usize,
+    },
+    /// The run has more players than the category allows.
+    #[error("the category allows up to {max} players, got {actual}")]
+    TooManyPlayers {
+        /// The largest number of players allowed.
+        max: i64,
+        /// The number of players in the run.
+        actual: usize,
+    },
+    /// A mandatory variable has no value.
+    #[error("variable `{name}` ({variable}) is mandatory")]
+    MissingVariable {
+        /// The variable.
+        variable: VariableId<'static>,
+        /// The name of the variable.
+        name: String,
+    },
+    /// A value was set for a variable that does not apply to the run.
+    #[error("variable {0} does not apply to this run")]
+    UnknownVariable(VariableId<'static>),
+    /// A value is not one of the values of the variable.
+    #[error("value {value} is not valid for variable `{name}` ({variable})")]
+    InvalidValue {
+        /// The variable.
+        variable: VariableId<'static>,
+        /// The name of the variable.
+        name: String,
+        /// The invalid value.
+        value: ValueId<'static>,
+    },
+    /// A user-defined value was set for a variable that only allows
+    /// pre-defined values.
+    #[error("variable `{name}` ({variable}) does not allow user-defined values")]
+    UserDefinedNotAllowed {
+        /// The variable.
+        variable: VariableId<'static>,
+        /// The name of the variable.
+        name: String,
+    },
+}
+
+/// Checks run submissions against the rules of a game and category.
+#[derive(Debug, Clone)]
+pub struct RunValidator<'a> {
+    game: types::Game<'a>,
+    category: types::Category<'a>,
+    variables: Vec<Variable<'a>>,
+}
+
+impl<'a> RunValidator<'a> {
+    /// Create a validator from data that was already fetched.
+    ///
+    /// `variables` are the variables of the category.
+    pub fn new(
+        game: types::Game<'a>,
+        category: types::Category<'a>,
+        variables: Vec<Variable<'a>>,
+    ) -> Self {
+        Self {
+            game,
+            category,
+            variables,
+        }
+    }
+
+    /// Check a run submission. Returns every rule the run does not follow.
+    pub fn validate(&self, run: &CreateRun<'_>) -> Result<(), Vec<ValidationError>> {
+        let mut errors = Vec::new();
+        self.check_category(run, &mut errors);
+        self.check_times(run, &mut errors);
+        self.check_system(run, &mut errors);
+        self.check_players(run, &mut errors);
+        self.check_variables(run, &mut errors);
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    fn check_category(&self, run: &CreateRun<'_>, errors: &mut Vec<ValidationError>) {
+        if *run.category() != self.category.id {
+            errors.push(ValidationError::WrongCategory(owned(run.category())));
+        }
+        match (&self.category.type_field, run.level()) {
+            (CategoryType::PerLevel, None) => errors.push(ValidationError::MissingLevel),
+            (CategoryType::PerGame, Some(_)) => errors.push(ValidationError::UnexpectedLevel),
+            _ => {}
+        }
+        if self.game.ruleset.require_video && run.video().is_none() {
+            errors.push(ValidationError::MissingVideo);
+        }
+    }
+
+    fn check_times(&self, run: &CreateRun<'_>, errors: &mut Vec<ValidationError>) {
+        let run_times = &self.game.ruleset.run_times;
+        let methods = [
+            TimingMethod::Realtime,
+            TimingMethod::RealtimeNoloads,
+            TimingMethod::Ingame,
+        ];
+        let mut has_time = false;
+        for method in methods.iter().filter(|method| run.time(**method).is_some()) {
+            if run_times.contains(method) {
+                has_time = true;
+            } else {
+                errors.push(ValidationError::TimingMethodNotAllowed(*method));
+            }
+        }
+        if !has_time {
+            errors.push(ValidationError::MissingTime(run_times.clone()));
+        }
+    }
+
+    fn check_system(&self, run: &CreateRun<'_>, errors: &mut Vec<ValidationError>) {
+        if run.emulated() == Some(true) && !self.game.ruleset.emulators_allowed {
+            errors.push(ValidationError::EmulatorNotAllowed);
+        }
+        match run.platform() {
+            None if !self.game.platforms.is_empty() => {
+                errors.push(ValidationError::MissingPlatform)
+            }
+            Some(platform) if !self.game.platforms.contains(platform) => {
+                errors.push(ValidationError::InvalidPlatform(owned(platform)))
+            }
+            _ => {}
+        }
+        match run.region() {
+            None if !self.game.regions.is_empty() => errors.push(ValidationError::MissingRegion),
+            Some(region) if !self.game.regions.contains(region) => {
+                errors.push(ValidationError::InvalidRegion(owned(region)))
+            }
+            _ => {}
+        }
+    }
+
+    fn check_players(&self, run: &CreateRun<'_>, errors: &mut Vec<ValidationError>) {
+        // Without any players, the run is submitted for the authenticated user.
+        let actual = run.players().len().max(1);
+        match self.category.players {
+            Players::Exactly(expected) if actual as i64 != expected => {
+                errors.push(ValidationError::WrongPlayerCount { expected, actual })
+            }
+            Players::UpTo(max) if actual as i64 > max => {
+                errors.push(ValidationError::TooManyPlayers { max, actual })
+            }
+            _ => {}
+        }
+    }
+
+    fn check_variables(&self, run: &CreateRun<'_>, errors: &mut Vec<ValidationError>) {
+        let applicable: Vec<_> = self
+            .variables
+            .iter()
+            .filter(|variable| variable.applies_to(&self.category.id, run.level()))
+            .collect();
+
+        for variable in &applicable {
+            if variable.mandatory && !run.variables().contains_key(&variable.id) {
+                errors.push(ValidationError::MissingVariable {
+                    variable: owned(&variable.id),
+                    name: variable.name.clone(),
+                });
+            }
+        }
+
+        for (id, value) in run.variables() {
+            let variable = match applicable.iter().find(|variable| variable.id == *id) {
+                Some(variable) => variable,
+                None => {
+                    errors.push(ValidationError::UnknownVariable(owned(id)));
+                    continue;
+                }
+            };
+            match value {
+                ValueType::PreDefined { value } => {
+                    if !variable.values.values.contains_key(value) {
+                        errors.push(ValidationError::InvalidValue {
+                            variable: owned(&variable.id),
+                            name: variable.name.clone(),
+                            value: owned(value),
+                        });
+                    }
+                }
+                ValueType::UserDefined { .. } => {
+                    if !variable.user_defined {
+                        errors.push(ValidationError::UserDefinedNotAllowed {
+                            variable: owned(&variable.id),
+                            name: variable.name.clone(),
+                        });
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl RunValidator<'static> {
+    /// Load the rules of a game and category using a synchronous client.
+    pub fn load<C, G, Cat>(client: &C, game: G, category: Cat) -> Result<Self, ApiError<C::Error>>
+    where
+        C: Client,
+        G: Into<GameId<'static>>,
+        Cat: Into<CategoryId<'static>>,
+    {
+        let (game, category, variables) = endpoints(game.into(), category.into());
+        Ok(Self::new(
+            game.query(client)?,
+            category.query(client)?,
+            variables.query(client)?,
+        ))
+    }
+
+    /// Load the rules of a game and category using an asynchronous client.
+    pub async fn load_async<C, G, Cat>(
+        client: &C,
+        game: G,
+        category: Cat,
+    ) -> Result<Self, ApiError<C::Error>>
+    where
+        C: AsyncClient + Sync,
+        G: Into<GameId<'static>>,
+        Cat: Into<CategoryId<'static>>,
+    {
+        let (game, category, variables) = endpoints(game.into(), category.into());
+        Ok(Self::new(
+            game.query_async(client).await?,
+            category.query_async(client).await?,
+            variables.query_async(client).await?,
+        ))
+    }
+}
+
+fn endpoints<'a>(
+    game: GameId<'a>,
+    category: CategoryId<'a>,
+) -> (Game<'a>, Category<'a>, CategoryVariables<'a>) {
+    (
+        Game::builder()
+            .id(game)
+            .build()
+            .expect("all required fields are set"),
+        Category::builder()
+            .id(category.clone())
+            .build()
+            .expect("all required fields are set"),
+        CategoryVariables::builder()
+            .id(category)
+            .build()
+            .expect("all required fields are set"),
+    )
+}
+
+/// Copy an `ID` so it can be returned in an error.
+fn owned<T, U>(id: &T) -> U
+where
+    T: ToString,
+    U: From<String>,
+{
+    U::from(id.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::{
+        api::runs::{CreateRun, Player, ValueType},
+        test_utils::{game_json, variable_json},
+        types::{RunTime, TimingMethod},
+    };
+
+    use super::{RunValidator, ValidationError};
+
+    fn validator(
+        players: serde_json::Value,
+        variables: Vec<serde_json::Value>,
+    ) -> RunValidator<'static> {
+        let category = json!({
+            "id": "wkpoo02r",
+            "name": "Any%",
+            "weblink": "https://www.speedrun.com/o1y9wo6q#Any",
+            "type": "per-game",
+            "rules": "",
+            "players": players,
+            "miscellaneous": false,
+            "links": [],
+        });
+        RunValidator::new(
+            serde_json::from_value(game_json("o1y9wo6q")).unwrap(),
+            serde_json::from_value(category).unwrap(),
+            variables
+                .into_iter()
+                .map(|variable| serde_json::from_value(variable).unwrap())
+                .collect(),
+        )
+    }
+
+    fn mandatory(id: &str) -> serde_json::Value {
+        let mut variable = variable_json(id, false, false, json!({ "type": "global" }));
+        variable["mandatory"] = json!(true);
+        variable
+    }
+
+    #[test]
+    fn valid_run() {
+        let validator = validator(
+            json!({ "type": "exactly", "value": 1 }),
+            vec![mandatory("var")],
+        );
+        let run = CreateRun::builder()
+            .category("wkpoo02r")
+            .realtime(RunTime::from_millis(1_000))
+            .variable("var", ValueType::PreDefined { value: "a".into() })
+            .build()
+            .unwrap();
+        assert_eq!(validator.validate(&run), Ok(()));
+    }
+
+    #[test]
+    fn reports_every_error() {
+        let validator = validator(json!({ "type": "exactly", "value": 2 }), vec![]);
+        let run = CreateRun::builder()
+            .category("other")
+            .level("level")
+            .ingame(RunTime::from_millis(1_000))
+            .emulated(true)
+            .player(Player::Guest {
+                name: "guest".into(),
+            })
+            .build()
+            .unwrap();
+        assert_eq!(
+            validator.validate(&run),
+            Err(vec![
+                ValidationError::WrongCategory("other".into()),
+                ValidationError::UnexpectedLevel,
+                ValidationError::TimingMethodNotAllowed(TimingMethod::Ingame),
+                ValidationError::MissingTime(vec![TimingMethod::Realtime]),
+                ValidationError::EmulatorNotAllowed,
+                ValidationError::WrongPlayerCount {
+                    expected: 2,
+                    actual: 1
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn checks_variables() {
+        let validator = validator(
+            json!({ "type": "up-to", "value": 4 }),
+            vec![
+                mandatory("required"),
+                variable_json("predefined", false, false, json!({ "type": "global" })),
+                variable_json(
+                    "level",
+                    false,
+                    false,
+                    json!({ "type": "single-level", "level": "l" }),
+                ),
+            ],
+        );
+        let run = CreateRun::builder()
+            .category("wkpoo02r")
+            .realtime(RunTime::from_millis(1_000))
+            .variable("predefined", ValueType::UserDefined { value: "a".into() })
+            .variable("level", ValueType::PreDefined { value: "a".into() })
+            .build()
+            .unwrap();
+        let mut errors = validator.validate(&run).unwrap_err();
+        errors.sort_by_key(|error| error.to_string());
+        assert_eq!(
+            errors,
+            vec![
+                ValidationError::UserDefinedNotAllowed {
+                    variable: "predefined".into(),
+                    name: "predefined".into(),
+                },
+                ValidationError::MissingVariable {
+                    variable: "required".into(),
+                    name: "required".into(),
+                },
+                ValidationError::UnknownVariable("level".into()),
+            ]
+        );
+
+        let run = CreateRun::builder()
+            .category("wkpoo02r")
+            .realtime(RunTime::from_millis(1_000))
+            .variable("required", ValueType::PreDefined { value: "c".into() })
+            .build()
+            .unwrap();
+        assert_eq!(
+            validator.validate(&run),
+            Err(vec![ValidationError::InvalidValue {
+                variable: "required".into(),
+                name: "required".into(),
+                value: "c".into(),
+            }])
+        );
+    }
+}